@@ -1,5 +1,8 @@
 use criterion::{black_box, criterion_group, criterion_main, Criterion};
-use sha3::{sha3_224, sha3_256, sha3_384, sha3_512, shake128, shake256};
+use sha3::{
+    kt128, kt256, sha3_224, sha3_256, sha3_384, sha3_512, shake128, shake256, turboshake128,
+    turboshake256,
+};
 
 fn sha3_benchmark(c: &mut Criterion) {
     c.bench_function("sha3_224", |b| b.iter(|| sha3_224(black_box(""))));
@@ -13,9 +16,20 @@ fn shake_benchmark(c: &mut Criterion) {
     c.bench_function("shake256", |b| b.iter(|| shake256(black_box(""), 512)));
 }
 
+fn turboshake_benchmark(c: &mut Criterion) {
+    c.bench_function("turboshake128", |b| {
+        b.iter(|| turboshake128(black_box(""), 256, 0x1F))
+    });
+    c.bench_function("turboshake256", |b| {
+        b.iter(|| turboshake256(black_box(""), 512, 0x1F))
+    });
+    c.bench_function("kt128", |b| b.iter(|| kt128(black_box(""), 256, "")));
+    c.bench_function("kt256", |b| b.iter(|| kt256(black_box(""), 512, "")));
+}
+
 criterion_group!(
   name = benches;
   config = Criterion::default().sample_size(250);
-  targets = sha3_benchmark, shake_benchmark
+  targets = sha3_benchmark, shake_benchmark, turboshake_benchmark
 );
 criterion_main!(benches);
@@ -1,4 +1,4 @@
-use sha3::{sha3_224, sha3_256, sha3_384, sha3_512, shake128, shake256};
+use sha3::{sha3_224, sha3_256, sha3_384, sha3_512, shake128, shake256, Sha3Hasher};
 
 fn main() {
     println!("{}", sha3_224(""));
@@ -7,4 +7,9 @@ fn main() {
     println!("{}", sha3_512(""));
     println!("{}", shake128("", 256));
     println!("{}", shake256("", 512));
+
+    let mut hasher = Sha3Hasher::new(256);
+    hasher.update("The quick brown fox ");
+    hasher.update("jumps over the lazy dog");
+    println!("{}", hasher.finalize());
 }
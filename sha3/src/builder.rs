@@ -0,0 +1,236 @@
+use crate::error::Error;
+use crate::input::Input;
+use crate::shake::check_output_length;
+use crate::sponge::{Sponge, XofReader};
+use crate::utils::bytes_to_hex;
+
+/// Builder for the general Keccak\[c\] sponge from section 5.2 of FIPS 202, where the capacity, suffix bits and number of rounds can be chosen freely. SHA-3, SHAKE and the legacy Keccak functions are all instances of this sponge, but it can also compute non-standard instances like Keccak[c=448] or SHAKE with a custom suffix.
+#[derive(Clone, Debug)]
+pub struct KeccakBuilder {
+    capacity: usize,
+    suffix: u8,
+    suffix_len: usize,
+    rounds: usize,
+}
+
+impl KeccakBuilder {
+    /// Start building a sponge with the given capacity in bits, without suffix bits and with the full 24 rounds.
+    pub fn new(capacity: usize) -> KeccakBuilder {
+        KeccakBuilder {
+            capacity,
+            suffix: 0,
+            suffix_len: 0,
+            rounds: 24,
+        }
+    }
+
+    /// Set the suffix bits which are appended to the message before the padding, like 01 for SHA-3 and 1111 for SHAKE. The bits are numbered like in FIPS 202, so the first bit of the suffix is the least significant one and SHA-3 is suffix(0b10, 2). There can be at most 7 suffix bits.
+    pub fn suffix(mut self, bits: u8, len: usize) -> KeccakBuilder {
+        self.suffix = bits;
+        self.suffix_len = len;
+        self
+    }
+
+    /// Set the number of rounds of the permutation, which is 24 for Keccak-f\[1600\] and less for Keccak-p[1600, n_r].
+    pub fn rounds(mut self, rounds: usize) -> KeccakBuilder {
+        self.rounds = rounds;
+        self
+    }
+
+    /// Check the parameters and create the sponge. The rate (1600 - capacity) has to be a positive multiple of the lane size of 64 bits, the suffix bits have to fit in their length of at most 7 bits and there can be at most 24 rounds.
+    pub fn build(&self) -> Result<KeccakSponge, Error> {
+        check_rate(self.capacity)?;
+
+        if self.suffix_len > 7 || self.suffix >> self.suffix_len != 0 {
+            return Err(Error::InvalidSuffix(self.suffix, self.suffix_len));
+        }
+
+        if self.rounds > 24 {
+            return Err(Error::InvalidRounds(self.rounds));
+        }
+
+        Ok(KeccakSponge {
+            sponge: Sponge::with_rounds(self.capacity, self.rounds),
+            // The domain separator is the suffix bits followed by the first padding bit.
+            suffix: self.suffix | 1 << self.suffix_len,
+        })
+    }
+}
+
+/// Check that the rate (1600 - capacity) of a Keccak\[c\] sponge is a positive multiple of the lane size of 64 bits.
+pub fn check_rate(capacity: usize) -> Result<(), Error> {
+    if capacity >= 1600 || !(1600 - capacity).is_multiple_of(64) {
+        return Err(Error::InvalidSpongeCapacity(capacity));
+    }
+
+    Ok(())
+}
+
+/// A Keccak\[c\] sponge created by the KeccakBuilder. It absorbs input like the other hashers, and any amount of output can be squeezed out of it.
+#[derive(Clone)]
+pub struct KeccakSponge {
+    sponge: Sponge,
+    suffix: u8,
+}
+
+impl KeccakSponge {
+    /// Add more input to the sponge, the input can be of any type that implements the Input trait.
+    pub fn update<T: Input>(&mut self, input: T) {
+        self.sponge.update(input.convert());
+    }
+
+    /// Consume the sponge and return the output with the given size in bits (has to be a number divisible by 8).
+    pub fn finalize(self, size: usize) -> String {
+        bytes_to_hex(&self.finalize_bytes(size))
+    }
+
+    /// Consume the sponge and return the raw bytes of the output with the given size in bits (has to be a number divisible by 8).
+    pub fn finalize_bytes(self, size: usize) -> Vec<u8> {
+        self.try_finalize_bytes(size)
+            .unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    /// Fallible version of finalize_bytes, which returns an error instead of panicking when the size is invalid.
+    pub fn try_finalize_bytes(self, size: usize) -> Result<Vec<u8>, Error> {
+        check_output_length(size)?;
+
+        Ok(self.sponge.finalize(self.suffix, size))
+    }
+
+    /// Consume the sponge and return a reader from which any amount of output can be read.
+    pub fn finalize_xof(self) -> XofReader {
+        self.sponge.finalize_xof(self.suffix)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::keccak::{keccak224, keccak256};
+    use crate::sha3::{sha3_224, sha3_512};
+    use crate::shake::shake128;
+    use crate::state::State;
+    use crate::turboshake::turboshake256;
+
+    const INPUT: &str = "The quick brown fox jumps over the lazy dog";
+
+    fn hash(builder: KeccakBuilder, size: usize) -> String {
+        let mut sponge = builder.build().unwrap();
+        sponge.update(INPUT);
+        sponge.finalize(size)
+    }
+
+    #[test]
+    fn test_standard_instances() {
+        assert_eq!(
+            hash(KeccakBuilder::new(448).suffix(0b10, 2), 224),
+            sha3_224(INPUT)
+        );
+        assert_eq!(
+            hash(KeccakBuilder::new(1024).suffix(0b10, 2), 512),
+            sha3_512(INPUT)
+        );
+        assert_eq!(
+            hash(KeccakBuilder::new(256).suffix(0b1111, 4), 1000),
+            shake128(INPUT, 1000)
+        );
+        assert_eq!(hash(KeccakBuilder::new(448), 224), keccak224(INPUT));
+        assert_eq!(hash(KeccakBuilder::new(512), 256), keccak256(INPUT));
+        assert_eq!(
+            hash(KeccakBuilder::new(512).suffix(0b1111, 4).rounds(12), 512),
+            turboshake256(INPUT, 512, 0x1F)
+        );
+    }
+
+    #[test]
+    fn test_custom_padding() {
+        // A short message has to be padded with the suffix bits and pad10*1 to exactly one block of (1600 - capacity) bits, and the output has to be squeezed in blocks of the same size, which is checked with the permutation directly.
+        for (capacity, suffix, len) in [
+            (576, 0b1010101, 7),
+            (64, 0b0, 0),
+            (1536, 0b11, 2),
+            (1024, 0b1, 1),
+        ] {
+            let rate = (1600 - capacity) / 8;
+            let mut block = b"abc".to_vec();
+            block.push(suffix | 1 << len);
+            block.resize(rate, 0x00);
+            block[rate - 1] ^= 0x80;
+
+            let mut state = State::new();
+            state.xor_bytes(0, &block);
+            state.permute();
+            let mut expected = vec![0; 2 * rate];
+            state.extract_bytes(0, &mut expected[..rate]);
+            state.permute();
+            state.extract_bytes(0, &mut expected[rate..]);
+
+            let mut sponge = KeccakBuilder::new(capacity)
+                .suffix(suffix, len)
+                .build()
+                .unwrap();
+            sponge.update("abc");
+            assert_eq!(sponge.finalize_bytes(2 * rate * 8), expected);
+        }
+    }
+
+    #[test]
+    fn test_custom_known_answers() {
+        // The expected outputs of these non-standard instances were computed with a bit-level Python implementation of algorithms 1-8 of FIPS 202, which gives the same results as the hashlib module for SHA3-256 and SHAKE128.
+        assert_eq!(
+            hash(KeccakBuilder::new(576).suffix(0b1010101, 7), 256),
+            "ba0fb98618bbc3ae9b5dabffb4325da2ee46c658c07925952cc3661f7c53a40f"
+        );
+        assert_eq!(
+            hash(KeccakBuilder::new(320).suffix(0b01, 2).rounds(6), 512),
+            "a5d4e4de002133b0c6af49d12d22edac4ef6636788984a79b5586c3d0a6a454aa6885bdc74517d784f04035bb5c331c3016f7394325ae2409c98606dc683721b"
+        );
+        assert_eq!(
+            hash(KeccakBuilder::new(64), 2400),
+            "b0b322edc47cc5614a79228b3f9e65000717bd3a6a71ce4bed1395e43d45c4b8c9415dc1255d80f980c24d075e731bd97a6937695f5b0652c4995d29b40e66a58d17462544f6752537a1d757f556c3f1f1e68fe9705364c44676c358a31096102fbe4745f10d10844db985d9d0082f89b55bc304a7325e9dd5306746514175e6c3002cb97ab01c88fa569ca186589b25a8ff92f29d62f605a92964a9d4c846c7aa15a10b9f28f0f23b7df45fe87f3697a1f71d8bb8206e2dc7410defa8bd537381a1f3e91907d5828207ae495246889b0dc1625f3e3e6e0a226467f734ebb7edb15013b78d65c52b3850b978a26adb5809a93663442585193f6e02547a7c25dd584ee0d8861d716f726d1d4e92e02e4885b00fe7df4153aab31b653fdca1404d139c68547e43aea9a1438d19"
+        );
+
+        // TurboSHAKE128 with the domain byte 0x07 (suffix 0b11 followed by the first padding bit) from the test vectors of RFC 9861.
+        let mut sponge = KeccakBuilder::new(256)
+            .suffix(0b11, 2)
+            .rounds(12)
+            .build()
+            .unwrap();
+        sponge.update([0xFF; 3].as_slice());
+        assert_eq!(
+            sponge.finalize(256),
+            "b658576001cad9b1e5f399a9f77723bba05458042d68206f7252682dba3663ed"
+        );
+    }
+
+    #[test]
+    fn test_invalid_parameters() {
+        assert_eq!(
+            KeccakBuilder::new(1600).build().err(),
+            Some(Error::InvalidSpongeCapacity(1600))
+        );
+        assert_eq!(
+            KeccakBuilder::new(100).build().err(),
+            Some(Error::InvalidSpongeCapacity(100))
+        );
+        assert_eq!(
+            KeccakBuilder::new(512).suffix(0b100, 2).build().err(),
+            Some(Error::InvalidSuffix(0b100, 2))
+        );
+        assert_eq!(
+            KeccakBuilder::new(512).suffix(0, 8).build().err(),
+            Some(Error::InvalidSuffix(0, 8))
+        );
+        assert_eq!(
+            KeccakBuilder::new(512).rounds(25).build().err(),
+            Some(Error::InvalidRounds(25))
+        );
+        assert_eq!(
+            KeccakBuilder::new(512)
+                .build()
+                .unwrap()
+                .try_finalize_bytes(12),
+            Err(Error::InvalidOutputLength(12))
+        );
+    }
+}
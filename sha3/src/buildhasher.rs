@@ -0,0 +1,122 @@
+use crate::turboshake::TurboShakeHasher;
+use std::collections::hash_map::RandomState;
+use std::hash::{BuildHasher, Hasher};
+
+/// The size of the key in bytes, which matches the 128 bit security level of TurboSHAKE128.
+const KEY_SIZE: usize = 16;
+
+/// Builds keyed hashers for a `HashMap` or `HashSet` whose keys can be chosen by an attacker, like `HashMap<K, V, Sha3BuildHasher>`. The hash of a key is TurboSHAKE128 of the secret key followed by the data written by `Hash`, so without the secret key it is not possible to find keys which all end up in the same bucket. This is a lot slower than the SipHash of the standard library. The hashing itself does not rely on SipHash, but the random key of `new` and `default` is made with SipHash by the standard library, so use `with_key` with a key from the random number generator of the operating system to not rely on SipHash at all.
+#[derive(Clone)]
+pub struct Sha3BuildHasher {
+    // TurboSHAKE128 with the key already absorbed, which is cloned for every new hasher.
+    hasher: TurboShakeHasher,
+}
+
+impl Sha3BuildHasher {
+    /// Create a builder with a random key. The crate has no source of randomness of its own, so the key is two SipHash-1-3 outputs of a RandomState of the standard library. Its SipHash keys are random for each thread, but every new RandomState in a thread only adds 1 to the first of them, so the key is no harder to find than the SipHash keys of the standard library.
+    pub fn new() -> Sha3BuildHasher {
+        let state = RandomState::new();
+        let mut key = [0; KEY_SIZE];
+        key[..8].copy_from_slice(&state.hash_one(0u8).to_le_bytes());
+        key[8..].copy_from_slice(&state.hash_one(1u8).to_le_bytes());
+
+        Sha3BuildHasher::with_key(key)
+    }
+
+    /// Create a builder with the given key, so the hashes are the same every time the program runs. The key has to be kept secret to protect against attacks.
+    pub fn with_key(key: [u8; KEY_SIZE]) -> Sha3BuildHasher {
+        let mut hasher = TurboShakeHasher::new(128, 0x1F);
+        hasher.update(key.as_slice());
+
+        Sha3BuildHasher { hasher }
+    }
+}
+
+impl Default for Sha3BuildHasher {
+    fn default() -> Sha3BuildHasher {
+        Sha3BuildHasher::new()
+    }
+}
+
+impl BuildHasher for Sha3BuildHasher {
+    type Hasher = Sha3MapHasher;
+
+    fn build_hasher(&self) -> Sha3MapHasher {
+        Sha3MapHasher {
+            hasher: self.hasher.clone(),
+        }
+    }
+}
+
+/// The hasher made by Sha3BuildHasher, which returns the first 64 bits of the TurboSHAKE128 output as a little endian number.
+#[derive(Clone)]
+pub struct Sha3MapHasher {
+    hasher: TurboShakeHasher,
+}
+
+impl Hasher for Sha3MapHasher {
+    fn write(&mut self, bytes: &[u8]) {
+        self.hasher.update(bytes);
+    }
+
+    fn finish(&self) -> u64 {
+        // finish does not consume the hasher and can be called more than once, so the output is read from a copy.
+        let mut out = [0; 8];
+        self.hasher.clone().finalize_xof().squeeze(&mut out);
+        u64::from_le_bytes(out)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::turboshake::turboshake_bytes;
+    use std::collections::{HashMap, HashSet};
+
+    #[test]
+    fn test_hash_is_turboshake128() {
+        let build = Sha3BuildHasher::with_key([7; 16]);
+        let mut hasher = build.build_hasher();
+        hasher.write(b"The quick brown fox ");
+        hasher.write(b"jumps over the lazy dog");
+
+        let mut input = vec![7; 16];
+        input.extend_from_slice(b"The quick brown fox jumps over the lazy dog");
+        let expected = turboshake_bytes(128, input, 64, 0x1F);
+        assert_eq!(
+            hasher.finish(),
+            u64::from_le_bytes(expected.try_into().unwrap())
+        );
+        assert_eq!(hasher.finish(), hasher.finish());
+    }
+
+    #[test]
+    fn test_keys() {
+        let a = Sha3BuildHasher::with_key([1; 16]);
+        let b = Sha3BuildHasher::with_key([1; 16]);
+        let c = Sha3BuildHasher::with_key([2; 16]);
+        assert_eq!(a.hash_one("key"), b.hash_one("key"));
+        assert_ne!(a.hash_one("key"), c.hash_one("key"));
+
+        // Random keys are different for every builder.
+        assert_ne!(
+            Sha3BuildHasher::new().hash_one("key"),
+            Sha3BuildHasher::new().hash_one("key")
+        );
+    }
+
+    #[test]
+    fn test_hash_map() {
+        let mut map: HashMap<String, usize, Sha3BuildHasher> = HashMap::default();
+        for i in 0..1000 {
+            map.insert(i.to_string(), i);
+        }
+        assert_eq!(map.len(), 1000);
+        assert_eq!(map.get("123"), Some(&123));
+        assert_eq!(map.get("1000"), None);
+
+        let mut set = HashSet::with_hasher(Sha3BuildHasher::with_key([0; 16]));
+        set.extend(["a", "b", "a"]);
+        assert_eq!(set.len(), 2);
+    }
+}
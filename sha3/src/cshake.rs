@@ -0,0 +1,242 @@
+use crate::error::Error;
+use crate::input::Input;
+use crate::shake::{check_capacity, check_output_length};
+use crate::sponge::{Sponge, XofReader};
+use crate::utils::{bytepad, bytes_to_hex, encode_string};
+
+/// The cSHAKE function as defined in section 3 of SP 800-185. The capacity (128 or 256), input and size in bits are the same as for the shake function. The function name is used by NIST to define functions based on cSHAKE, and the customization string can be chosen freely to get a different function for each use. When both of them are empty, cSHAKE is the same as SHAKE. Panics if the capacity or size is invalid.
+pub fn cshake<T: Input, N: Input, S: Input>(
+    capacity: usize,
+    input: T,
+    size: usize,
+    function_name: N,
+    customization: S,
+) -> String {
+    bytes_to_hex(&cshake_bytes(
+        capacity,
+        input,
+        size,
+        function_name,
+        customization,
+    ))
+}
+
+/// The cSHAKE function returning the raw bytes of the output instead of a hex string. The parameters are the same as for the cshake function.
+pub fn cshake_bytes<T: Input, N: Input, S: Input>(
+    capacity: usize,
+    input: T,
+    size: usize,
+    function_name: N,
+    customization: S,
+) -> Vec<u8> {
+    try_cshake_bytes(capacity, input, size, function_name, customization)
+        .unwrap_or_else(|e| panic!("{}", e))
+}
+
+/// Fallible version of the cshake function, which returns an error instead of panicking when the capacity or size is invalid.
+pub fn try_cshake<T: Input, N: Input, S: Input>(
+    capacity: usize,
+    input: T,
+    size: usize,
+    function_name: N,
+    customization: S,
+) -> Result<String, Error> {
+    try_cshake_bytes(capacity, input, size, function_name, customization)
+        .map(|output| bytes_to_hex(&output))
+}
+
+/// Fallible version of the cshake_bytes function, which returns an error instead of panicking when the capacity or size is invalid.
+pub fn try_cshake_bytes<T: Input, N: Input, S: Input>(
+    capacity: usize,
+    input: T,
+    size: usize,
+    function_name: N,
+    customization: S,
+) -> Result<Vec<u8>, Error> {
+    let mut hasher = CShakeHasher::try_new(capacity, function_name, customization)?;
+    hasher.update(input);
+    hasher.try_finalize_bytes(size)
+}
+
+/// Shortcut for the cSHAKE128 function, the parameters are the same as for the cshake function.
+pub fn cshake128<T: Input, N: Input, S: Input>(
+    input: T,
+    size: usize,
+    function_name: N,
+    customization: S,
+) -> String {
+    cshake(128, input, size, function_name, customization)
+}
+
+/// Shortcut for the cSHAKE256 function, the parameters are the same as for the cshake function.
+pub fn cshake256<T: Input, N: Input, S: Input>(
+    input: T,
+    size: usize,
+    function_name: N,
+    customization: S,
+) -> String {
+    cshake(256, input, size, function_name, customization)
+}
+
+/// Incremental version of the cSHAKE function for input which is not available all at once. It works like the ShakeHasher, but with a function name and customization string.
+#[derive(Clone)]
+pub struct CShakeHasher {
+    sponge: Sponge,
+    // The domain separator and first padding bit, which depends on whether this is plain SHAKE or not.
+    suffix: u8,
+}
+
+impl CShakeHasher {
+    /// Create a new hasher with the given capacity (128 or 256), function name and customization string. Panics if the capacity is invalid.
+    pub fn new<N: Input, S: Input>(
+        capacity: usize,
+        function_name: N,
+        customization: S,
+    ) -> CShakeHasher {
+        CShakeHasher::try_new(capacity, function_name, customization)
+            .unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    /// Fallible version of new, which returns an error instead of panicking when the capacity is invalid.
+    pub fn try_new<N: Input, S: Input>(
+        capacity: usize,
+        function_name: N,
+        customization: S,
+    ) -> Result<CShakeHasher, Error> {
+        check_capacity(capacity)?;
+
+        let mut sponge = Sponge::new(capacity * 2);
+        let (n, s) = (function_name.convert(), customization.convert());
+
+        // When both N and S are empty, cSHAKE is defined to be plain SHAKE.
+        if n.is_empty() && s.is_empty() {
+            return Ok(CShakeHasher {
+                sponge,
+                suffix: 0x1F,
+            });
+        }
+
+        // Absorb bytepad(encode_string(N) || encode_string(S), rate) in front of the input, which fills up a whole number of blocks.
+        let mut prefix = encode_string(n);
+        prefix.extend(encode_string(s));
+        let rate = sponge.rate();
+        sponge.update(&bytepad(prefix, rate));
+
+        // The cSHAKE domain separator is the bits 00 followed by the first padding bit.
+        Ok(CShakeHasher {
+            sponge,
+            suffix: 0x04,
+        })
+    }
+
+    /// Add more input to the hasher, the input can be of any type that implements the Input trait.
+    pub fn update<T: Input>(&mut self, input: T) {
+        self.sponge.update(input.convert());
+    }
+
+    /// Consume the hasher and return the output with the given size in bits (has to be a number divisible by 8).
+    pub fn finalize(self, size: usize) -> String {
+        bytes_to_hex(&self.finalize_bytes(size))
+    }
+
+    /// Consume the hasher and return the raw bytes of the output with the given size in bits (has to be a number divisible by 8).
+    pub fn finalize_bytes(self, size: usize) -> Vec<u8> {
+        self.try_finalize_bytes(size)
+            .unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    /// Fallible version of finalize_bytes, which returns an error instead of panicking when the size is invalid.
+    pub fn try_finalize_bytes(self, size: usize) -> Result<Vec<u8>, Error> {
+        check_output_length(size)?;
+
+        Ok(self.sponge.finalize(self.suffix, size))
+    }
+
+    /// Consume the hasher and return a reader from which any amount of output can be read.
+    pub fn finalize_xof(self) -> XofReader {
+        self.sponge.finalize_xof(self.suffix)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::shake::{shake128, shake256};
+
+    #[test]
+    fn test_cshake128() {
+        // Sample #1 and #2 from the cSHAKE samples of NIST.
+        assert_eq!(
+            cshake128(
+                [0x00, 0x01, 0x02, 0x03].as_slice(),
+                256,
+                "",
+                "Email Signature"
+            ),
+            "c1c36925b6409a04f1b504fcbca9d82b4017277cb5ed2b2065fc1d3814d5aaf5"
+        );
+
+        let input: Vec<u8> = (0x00..=0xC7).collect();
+        assert_eq!(
+            cshake128(input, 256, "", "Email Signature"),
+            "c5221d50e4f822d96a2e8881a961420f294b7b24fe3d2094baed2c6524cc166b"
+        );
+    }
+
+    #[test]
+    fn test_cshake256() {
+        // Sample #3 and #4 from the cSHAKE samples of NIST.
+        assert_eq!(
+            cshake256([0x00, 0x01, 0x02, 0x03].as_slice(), 512, "", "Email Signature"),
+            "d008828e2b80ac9d2218ffee1d070c48b8e4c87bff32c9699d5b6896eee0edd164020e2be0560858d9c00c037e34a96937c561a74c412bb4c746469527281c8c"
+        );
+
+        let input: Vec<u8> = (0x00..=0xC7).collect();
+        assert_eq!(
+            cshake256(input, 512, "", "Email Signature"),
+            "07dc27b11e51fbac75bc7b3c1d983e8b4b85fb1defaf218912ac86430273091727f42b17ed1df63e8ec118f04b23633c1dfb1574c8fb55cb45da8e25afb092bb"
+        );
+    }
+
+    #[test]
+    fn test_cshake_is_shake() {
+        assert_eq!(cshake128("abc", 256, "", ""), shake128("abc", 256));
+        assert_eq!(cshake256("abc", 512, "", ""), shake256("abc", 512));
+        assert_ne!(cshake128("abc", 256, "N", ""), shake128("abc", 256));
+    }
+
+    #[test]
+    fn test_cshake_hasher() {
+        let mut hasher = CShakeHasher::new(128, "Name", "Customization");
+        hasher.update("The quick brown fox ");
+        hasher.update("jumps over the lazy dog");
+        let mut reader = hasher.finalize_xof();
+
+        let mut out = vec![0; 400];
+        reader.squeeze(&mut out[..100]);
+        reader.squeeze(&mut out[100..]);
+
+        assert_eq!(
+            out,
+            cshake_bytes(
+                128,
+                "The quick brown fox jumps over the lazy dog",
+                8 * 400,
+                "Name",
+                "Customization"
+            )
+        );
+    }
+
+    #[test]
+    fn test_try_cshake() {
+        assert_eq!(
+            try_cshake(512, "", 256, "", ""),
+            Err(Error::InvalidCapacity(512))
+        );
+        assert_eq!(
+            try_cshake(256, "", 7, "", "S"),
+            Err(Error::InvalidOutputLength(7))
+        );
+    }
+}
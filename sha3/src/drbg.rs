@@ -0,0 +1,437 @@
+use crate::error::Error;
+use crate::input::Input;
+use crate::sha3::{check_size, Sha3Hasher};
+
+/// The maximum number of requests between reseeds from table 2 of SP 800-90A.
+const RESEED_INTERVAL: u64 = 1 << 48;
+
+/// The maximum number of bytes per request from table 2 of SP 800-90A, which is 2^19 bits.
+const MAX_REQUEST: usize = 1 << 16;
+
+/// The Hash_DRBG deterministic random bit generator as defined in section 10.1.1 of SP 800-90A with SHA-3 as the hash function. It does not have its own entropy source, so the entropy input has to be given when the DRBG is instantiated and reseeded. The size is the size of the SHA-3 hash (224, 256, 384 or 512), which gives a security strength of 112, 128, 192 or 256 bits.
+#[derive(Clone)]
+pub struct HashDrbg {
+    size: usize,
+    // The length of V and C in bytes, which is seedlen from table 2 of SP 800-90A (440 or 888 bits).
+    seed_len: usize,
+    v: Vec<u8>,
+    c: Vec<u8>,
+    reseed_counter: u64,
+    reseed_interval: u64,
+    prediction_resistance: bool,
+}
+
+impl HashDrbg {
+    /// Instantiate the DRBG as described in section 10.1.1.2 of SP 800-90A. The entropy input has to be at least as long as the security strength, and the nonce and personalization string can be of any type that implements the Input trait. When prediction resistance is requested, every request has to come with new entropy input through generate_with_prediction_resistance. Returns an error if the size or length of the entropy input is invalid.
+    pub fn new<E: Input, N: Input, P: Input>(
+        size: usize,
+        entropy: E,
+        nonce: N,
+        personalization: P,
+        prediction_resistance: bool,
+    ) -> Result<HashDrbg, Error> {
+        check_size(size)?;
+
+        let mut drbg = HashDrbg {
+            size,
+            seed_len: if size <= 256 { 55 } else { 111 },
+            v: Vec::new(),
+            c: Vec::new(),
+            reseed_counter: 1,
+            reseed_interval: RESEED_INTERVAL,
+            prediction_resistance,
+        };
+        drbg.check_entropy(entropy.convert())?;
+
+        // seed_material = entropy_input || nonce || personalization_string
+        let mut seed_material = entropy.convert().to_vec();
+        seed_material.extend_from_slice(nonce.convert());
+        seed_material.extend_from_slice(personalization.convert());
+        drbg.set_seed(&seed_material);
+
+        Ok(drbg)
+    }
+
+    /// Reseed the DRBG with new entropy input and optional additional input as described in section 10.1.1.3 of SP 800-90A. Returns an error if the entropy input is shorter than the security strength.
+    pub fn reseed<E: Input, A: Input>(
+        &mut self,
+        entropy: E,
+        additional_input: A,
+    ) -> Result<(), Error> {
+        self.check_entropy(entropy.convert())?;
+
+        // seed_material = 0x01 || V || entropy_input || additional_input
+        let mut seed_material = vec![0x01];
+        seed_material.extend_from_slice(&self.v);
+        seed_material.extend_from_slice(entropy.convert());
+        seed_material.extend_from_slice(additional_input.convert());
+        self.set_seed(&seed_material);
+
+        Ok(())
+    }
+
+    /// Fill the buffer with random bytes as described in section 10.1.1.4 of SP 800-90A, with optional additional input (which can be empty). Returns an error if the buffer is longer than 2^16 bytes, or a ReseedRequired error if the DRBG has to be reseeded first because the reseed interval has passed. The DRBG has no entropy source to reseed itself from like section 9.3.1 allows, so a DRBG instantiated with prediction resistance always returns ReseedRequired here without changing its state, and generate_with_prediction_resistance has to be used instead.
+    pub fn generate<A: Input>(
+        &mut self,
+        buffer: &mut [u8],
+        additional_input: A,
+    ) -> Result<(), Error> {
+        if self.prediction_resistance || self.reseed_counter > self.reseed_interval {
+            return Err(Error::ReseedRequired);
+        }
+
+        self.generate_bytes(buffer, additional_input.convert())
+    }
+
+    /// Fill the buffer with random bytes with prediction resistance as described in section 9.3.1 of SP 800-90A, where the DRBG is reseeded with the new entropy input and the additional input before generating the output. Returns an error if the entropy input or the length of the buffer is invalid.
+    pub fn generate_with_prediction_resistance<E: Input, A: Input>(
+        &mut self,
+        buffer: &mut [u8],
+        entropy: E,
+        additional_input: A,
+    ) -> Result<(), Error> {
+        if buffer.len() > MAX_REQUEST {
+            return Err(Error::InvalidRequestLength(buffer.len()));
+        }
+
+        // The additional input is used for the reseed, so it is not used again for the output.
+        self.reseed(entropy, additional_input)?;
+        self.generate_bytes(buffer, &[])
+    }
+
+    /// The number of requests since the DRBG was instantiated or reseeded, plus one.
+    pub fn reseed_counter(&self) -> u64 {
+        self.reseed_counter
+    }
+
+    /// The generate process of section 10.1.1.4 of SP 800-90A, without the checks for reseeding.
+    fn generate_bytes(&mut self, buffer: &mut [u8], additional_input: &[u8]) -> Result<(), Error> {
+        if buffer.len() > MAX_REQUEST {
+            return Err(Error::InvalidRequestLength(buffer.len()));
+        }
+
+        // Step 2: w = Hash(0x02 || V || additional_input) and V = (V + w) mod 2^seedlen.
+        if !additional_input.is_empty() {
+            let w = self.hash(&[&[0x02], &self.v, additional_input]);
+            add(&mut self.v, &w);
+        }
+
+        // Step 3 is the Hashgen process from section 10.1.1.4, where the output is the hash of V, V + 1, V + 2 and so on.
+        let mut data = self.v.clone();
+        for chunk in buffer.chunks_mut(self.size / 8) {
+            let block = self.hash(&[&data]);
+            chunk.copy_from_slice(&block[..chunk.len()]);
+            add(&mut data, &[0x01]);
+        }
+
+        // Step 4-6: V = (V + H + C + reseed_counter) mod 2^seedlen, where H = Hash(0x03 || V).
+        let h = self.hash(&[&[0x03], &self.v]);
+        let c = self.c.clone();
+        add(&mut self.v, &h);
+        add(&mut self.v, &c);
+        add(&mut self.v, &self.reseed_counter.to_be_bytes());
+        self.reseed_counter += 1;
+
+        Ok(())
+    }
+
+    /// Set V and C from the seed material, which is the same for instantiating and reseeding.
+    fn set_seed(&mut self, seed_material: &[u8]) {
+        self.v = self.hash_df(seed_material);
+
+        let mut c_material = vec![0x00];
+        c_material.extend_from_slice(&self.v);
+        self.c = self.hash_df(&c_material);
+
+        self.reseed_counter = 1;
+    }
+
+    /// The Hash_df derivation function from section 10.3.1 of SP 800-90A, which hashes the input to seedlen bits.
+    fn hash_df(&self, input: &[u8]) -> Vec<u8> {
+        let bits = (self.seed_len as u32 * 8).to_be_bytes();
+        let mut temp = Vec::new();
+
+        // Hash the counter (a single byte starting at 1), the number of bits to return and the input, until there is enough output.
+        for counter in 1..=self.seed_len.div_ceil(self.size / 8) {
+            temp.extend(self.hash(&[&[counter as u8], &bits, input]));
+        }
+
+        temp.truncate(self.seed_len);
+        temp
+    }
+
+    /// Hash the concatenation of the parts with SHA-3.
+    fn hash(&self, parts: &[&[u8]]) -> Vec<u8> {
+        let mut hasher = Sha3Hasher::new(self.size);
+        for part in parts {
+            hasher.update(*part);
+        }
+        hasher.finalize_bytes()
+    }
+
+    /// Check that the entropy input is at least as long as the security strength, which is half of the hash size.
+    fn check_entropy(&self, entropy: &[u8]) -> Result<(), Error> {
+        let strength = if self.size == 224 { 14 } else { self.size / 16 };
+
+        if entropy.len() < strength {
+            return Err(Error::InvalidEntropyLength(entropy.len()));
+        }
+
+        Ok(())
+    }
+}
+
+/// Add x to the big endian number a modulo 2^(8 * a.len()), where x is at most as long as a.
+fn add(a: &mut [u8], x: &[u8]) {
+    let mut carry = 0;
+
+    for i in 0..a.len() {
+        let digit = if i < x.len() { x[x.len() - 1 - i] } else { 0 };
+        let sum = a[a.len() - 1 - i] as u16 + digit as u16 + carry;
+        a[a.len() - 1 - i] = sum as u8;
+        carry = sum >> 8;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::{bytes_to_hex, hex_to_bytes};
+    use std::fs;
+    use std::path::Path;
+
+    /// Run the tests of a DRBG response file in the CAVP format, and return the number of tests which were run. Sections for other hash functions than SHA-3 are skipped. Each test instantiates the DRBG, reseeds it if there is a reseed input and generates output twice, and the second output has to match ReturnedBits.
+    fn run_cavp(contents: &str) -> usize {
+        let mut size = None;
+        let mut prediction_resistance = false;
+        let mut fields: Vec<(String, Vec<u8>)> = Vec::new();
+        let mut count = 0;
+
+        for line in contents.lines().map(str::trim) {
+            if let Some(section) = line.strip_prefix('[').and_then(|l| l.strip_suffix(']')) {
+                match section {
+                    "SHA3-224" => size = Some(224),
+                    "SHA3-256" => size = Some(256),
+                    "SHA3-384" => size = Some(384),
+                    "SHA3-512" => size = Some(512),
+                    "PredictionResistance = True" => prediction_resistance = true,
+                    "PredictionResistance = False" => prediction_resistance = false,
+                    _ if !section.contains('=') => size = None,
+                    _ => {}
+                }
+                continue;
+            }
+
+            let Some((name, value)) = line.split_once('=') else {
+                continue;
+            };
+            let (name, value) = (name.trim(), value.trim());
+            if name == "COUNT" {
+                fields.clear();
+                continue;
+            }
+            fields.push((name.to_string(), hex_to_bytes(value).unwrap()));
+
+            if name != "ReturnedBits" {
+                continue;
+            }
+            let Some(size) = size else {
+                continue;
+            };
+
+            let get = |name: &str| -> Vec<&[u8]> {
+                fields
+                    .iter()
+                    .filter(|(n, _)| n == name)
+                    .map(|(_, v)| v.as_slice())
+                    .collect()
+            };
+            let expected = get("ReturnedBits")[0];
+            let additional = get("AdditionalInput");
+            let mut drbg = HashDrbg::new(
+                size,
+                get("EntropyInput")[0],
+                get("Nonce")[0],
+                get("PersonalizationString")[0],
+                prediction_resistance,
+            )
+            .unwrap();
+
+            let mut out = vec![0; expected.len()];
+            if prediction_resistance {
+                let entropy = get("EntropyInputPR");
+                for i in 0..2 {
+                    drbg.generate_with_prediction_resistance(&mut out, entropy[i], additional[i])
+                        .unwrap();
+                }
+            } else {
+                if let Some(entropy) = get("EntropyInputReseed").first() {
+                    drbg.reseed(*entropy, get("AdditionalInputReseed")[0])
+                        .unwrap();
+                }
+                for input in &additional[..2] {
+                    drbg.generate(&mut out, *input).unwrap();
+                }
+            }
+
+            assert_eq!(bytes_to_hex(&out), bytes_to_hex(expected));
+            count += 1;
+        }
+
+        count
+    }
+
+    #[test]
+    fn test_cavp_format() {
+        // Vectors in the CAVP format for SHA3-256 without and SHA3-512 with prediction resistance. NIST only publishes Hash_DRBG vectors for SHA-1 and SHA-2, so these were computed with a Python implementation of section 10.1.1 of SP 800-90A using the hashlib module.
+        let vectors = "
+[SHA3-256]
+[PredictionResistance = False]
+[EntropyInputLen = 256]
+[NonceLen = 128]
+[PersonalizationStringLen = 256]
+[AdditionalInputLen = 256]
+[ReturnedBitsLen = 1024]
+
+COUNT = 1
+EntropyInput = 26bfb405320025ee57d5c3e10a0ed66da327d3613385ada96eafd56568e34d1e
+Nonce = d64eec77d0a70830a84f05fd633c0b48
+PersonalizationString = e7cbecba1bbe68a93afd7b3e160d4f8a7f272c690f09b3ca198134b00921be56
+EntropyInputReseed = bd7c216aeb6334dc4dc06d639f9acde95bc1097f9903541883146890440f59ed
+AdditionalInputReseed = df2cc22e8cf5788841cbcea82810a013413030c3a152236be2878fd3b295feaa
+AdditionalInput = 9c021f3833eda8c27db0b7fc03c0688003e2bda68aa915bb0f2901b3b350085a
+AdditionalInput = 32682b38a28d54da85a12be7bef3f3ac64397a2285b5b24a3edbcff8498889f2
+ReturnedBits = b3922488311df8108dd2e94a96a100cc95b6b17a6e9cc1c44d212c4bd83446543513ff1d3f92437732b4e7f6d813066a5a9a9767a1e34b7bcfc0b4a19dde043c9c20572e9e82be5358ce693ed2c72e825306ddda1ea79be5408bd67f4236666a2156f9c90c2d96e02440a8ddc5df7508bf2f054ea86dea6cde3017915b2a62d3
+
+[SHA-256]
+[PredictionResistance = False]
+
+COUNT = 0
+EntropyInput = 00
+ReturnedBits = 00
+
+[SHA3-512]
+[PredictionResistance = True]
+[EntropyInputLen = 256]
+[NonceLen = 128]
+[PersonalizationStringLen = 256]
+[AdditionalInputLen = 256]
+[ReturnedBitsLen = 1024]
+
+COUNT = 1
+EntropyInput = d7df2094ba04199f4d4d82223ffa1de5d2d38b0313b45761501096e0748534ea
+Nonce = 9964f5530103d0fed2b30dbd541c1b0d
+PersonalizationString = d0bca9866e7fb5033a31f57fc4409834075bd91858f53c1fcb4f85331226563e
+AdditionalInput = 5aa5422257c417c41676c9e34afffaab237c4a1e0eea1d66d163416c22a8f8f2
+EntropyInputPR = aa299fc98eb298607ae776d509ff74ef3c372d7ad4d37f09dbc43c196a0a8ddd
+AdditionalInput = 7f7735c0547a1ca6be9874d90b341159112697e4d19a85aa48a82c2cbc796892
+EntropyInputPR = 917e86010c008299bea1dd9fc557a6bc28883883e65520988f4db7aae6a58d72
+ReturnedBits = c50d9b65f24a192bfd5e37fb77711ed4cfa363651d55da92aa3dd06d6dfa9b31b81b8f1cb671fba402f811a23ce6542b6890dfb01e2cddfef87efad5621ec25d70c31e380e5755516981b25c922b9e6e18b4d2dcc533ed4a827c64ce9fb46288e3b98705c39fd9d18e3424e40e1efddba837634454097e6f101e82f4e0f52a18
+
+COUNT = 0
+EntropyInput = 3902f0e8ecad86702d2562dae853a3f64bd3064c41d06495100f90835d8eabbb
+Nonce = 3652489e3ed69701b87ae7d79bdb5764
+PersonalizationString =
+AdditionalInput =
+EntropyInputPR = 09376651b30831df0939f3a4486dc16e57096904b7fbb222d1a5e47f586cb250
+AdditionalInput =
+EntropyInputPR = 9f9b52187a316ba63a84ee9fb7efdb76145db722e2e12d6cee6115900529cb92
+ReturnedBits = 06af8e139d7586608ab6fb1b44c05ee7755d1e9d583806a48500d4a28055569e115fe6f516bdfd3b984bce30a02a94f4c64ce44cc494da8a888ea12f2d68dab38166cd9cbe6c82fe19cbe9b35e28213f9b0854e5b7a70e9473df523fb8b347e6b217514571ed440a1304bc49dea80a1f67e139cc97bd0c432cbb324d9ba0467e
+";
+        assert_eq!(run_cavp(vectors), 3);
+    }
+
+    #[test]
+    #[ignore = "needs CAVP response files in the cavp directory of the crate"]
+    fn test_cavp_files() {
+        // The CAVP response files are not part of the repository, so this test only runs with `cargo test -- --ignored` after they have been placed in the cavp directory.
+        let dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("cavp");
+        assert!(dir.is_dir(), "{} does not exist", dir.display());
+
+        let mut files = 0;
+        for path in fs::read_dir(&dir)
+            .unwrap()
+            .map(|entry| entry.unwrap().path())
+        {
+            if path.extension().is_some_and(|ext| ext == "rsp") {
+                let count = run_cavp(&fs::read_to_string(&path).unwrap());
+                assert!(count > 0, "{} has no SHA-3 test vectors", path.display());
+                files += 1;
+            }
+        }
+        assert!(files > 0, "{} has no .rsp files", dir.display());
+    }
+
+    #[test]
+    fn test_generate() {
+        let mut drbg = HashDrbg::new(256, [0x01; 32].as_slice(), "nonce", "", false).unwrap();
+        let mut a = [0; 100];
+        let mut b = [0; 100];
+        drbg.generate(&mut a, "").unwrap();
+        drbg.generate(&mut b, "").unwrap();
+        assert_ne!(a, b);
+        assert_eq!(drbg.reseed_counter(), 3);
+
+        // The same inputs give the same output.
+        let mut again = HashDrbg::new(256, [0x01; 32].as_slice(), "nonce", "", false).unwrap();
+        let mut c = [0; 100];
+        again.generate(&mut c, "").unwrap();
+        assert_eq!(a, c);
+
+        drbg.reseed([0x02; 32].as_slice(), "").unwrap();
+        assert_eq!(drbg.reseed_counter(), 1);
+    }
+
+    #[test]
+    fn test_errors() {
+        assert_eq!(
+            HashDrbg::new(256, [0; 15].as_slice(), "", "", false).err(),
+            Some(Error::InvalidEntropyLength(15))
+        );
+        assert_eq!(
+            HashDrbg::new(100, [0; 32].as_slice(), "", "", false).err(),
+            Some(Error::InvalidSize(100))
+        );
+
+        let mut drbg = HashDrbg::new(512, [0; 32].as_slice(), "", "", false).unwrap();
+        assert_eq!(
+            drbg.generate(&mut vec![0; MAX_REQUEST + 1], ""),
+            Err(Error::InvalidRequestLength(MAX_REQUEST + 1))
+        );
+
+        // After the reseed interval, the DRBG has to be reseeded.
+        drbg.reseed_interval = 2;
+        drbg.generate(&mut [0; 16], "").unwrap();
+        drbg.generate(&mut [0; 16], "").unwrap();
+        assert_eq!(drbg.generate(&mut [0; 16], ""), Err(Error::ReseedRequired));
+        drbg.reseed([0; 32].as_slice(), "").unwrap();
+        assert!(drbg.generate(&mut [0; 16], "").is_ok());
+    }
+
+    #[test]
+    fn test_prediction_resistance() {
+        // With prediction resistance, generate always returns an error and leaves both the DRBG and the buffer untouched.
+        let mut drbg = HashDrbg::new(256, [0; 32].as_slice(), "", "", true).unwrap();
+        let mut out = [0; 16];
+        assert_eq!(drbg.generate(&mut out, ""), Err(Error::ReseedRequired));
+        assert_eq!(
+            drbg.generate(&mut out, "additional"),
+            Err(Error::ReseedRequired)
+        );
+        assert_eq!(out, [0; 16]);
+        assert_eq!(drbg.reseed_counter(), 1);
+
+        // Every request has to come with new entropy, which is used for a reseed with the additional input before the output is generated.
+        let mut expected = HashDrbg::new(256, [0; 32].as_slice(), "", "", false).unwrap();
+        expected
+            .reseed([0x01; 32].as_slice(), "additional")
+            .unwrap();
+        let mut expected_out = [0; 16];
+        expected.generate(&mut expected_out, "").unwrap();
+
+        drbg.generate_with_prediction_resistance(&mut out, [0x01; 32].as_slice(), "additional")
+            .unwrap();
+        assert_eq!(out, expected_out);
+        assert_eq!(drbg.reseed_counter(), 2);
+    }
+}
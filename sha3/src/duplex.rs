@@ -0,0 +1,135 @@
+use crate::builder::check_rate;
+use crate::error::Error;
+use crate::input::Input;
+use crate::shake::check_output_length;
+use crate::state::State;
+use crate::utils::pad10star1;
+
+/// The duplex construction from "Duplexing the sponge" by the Keccak team. Unlike the sponge, which absorbs all input before squeezing out the output, each duplexing call absorbs one padded block of input and immediately returns output from the same state. The output of a call depends on the input of all calls before it, which is what protocols like authenticated encryption are built on.
+#[derive(Clone)]
+pub struct Duplex {
+    state: State,
+    // The rate of the duplex in bytes, which is the size of a block.
+    rate: usize,
+}
+
+impl Duplex {
+    /// Create a new duplex object with the given capacity in bits. Panics if the rate (1600 - capacity) is not a positive multiple of 64.
+    pub fn new(capacity: usize) -> Duplex {
+        Duplex::try_new(capacity).unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    /// Fallible version of new, which returns an error instead of panicking when the capacity is invalid.
+    pub fn try_new(capacity: usize) -> Result<Duplex, Error> {
+        check_rate(capacity)?;
+
+        Ok(Duplex {
+            state: State::new(),
+            rate: (1600 - capacity) / 8,
+        })
+    }
+
+    /// The rate of the duplex in bytes.
+    pub fn rate(&self) -> usize {
+        self.rate
+    }
+
+    /// Absorb the input padded with pad10*1 into the state and return the first size bits of the state after the permutation. The input has to be shorter than the rate to leave room for the padding, and the size (a number divisible by 8) can be at most the rate. Panics if the input or size is invalid.
+    pub fn duplexing<T: Input>(&mut self, input: T, size: usize) -> Vec<u8> {
+        self.try_duplexing(input, size)
+            .unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    /// Fallible version of duplexing, which returns an error instead of panicking when the input or size is invalid.
+    pub fn try_duplexing<T: Input>(&mut self, input: T, size: usize) -> Result<Vec<u8>, Error> {
+        let input = input.convert();
+
+        if input.len() >= self.rate {
+            return Err(Error::InvalidDuplexInput(input.len()));
+        }
+
+        check_output_length(size)?;
+        if size / 8 > self.rate {
+            return Err(Error::InvalidDuplexOutput(size));
+        }
+
+        // There are no suffix bits, so the first padding bit is right after the input.
+        Ok(self.duplex(input, 0x01, size))
+    }
+
+    /// Do a duplexing call where the input is followed by the given suffix byte, which holds the suffix bits and the first padding bit like the domain separators of the sponge. The input and size are not checked, so the caller has to make sure they fit in a block.
+    pub(crate) fn duplex(&mut self, input: &[u8], suffix: u8, size: usize) -> Vec<u8> {
+        // The padding is done for every call, so the input always fits in a single block.
+        let mut block = input.to_vec();
+        block.push(suffix);
+        self.state.xor_bytes(0, &pad10star1(block, self.rate));
+        self.state.permute();
+
+        let mut out = vec![0; size / 8];
+        self.state.extract_bytes(0, &mut out);
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::keccak::{keccak512_bytes, keccak_bytes};
+
+    #[test]
+    fn test_single_call_is_sponge() {
+        // A single duplexing call is the same as the sponge with pad10*1 and no suffix, which is the legacy Keccak function.
+        for length in [0, 1, 8, 100, 134, 135] {
+            let input: Vec<u8> = (0..length as u8).collect();
+
+            let mut duplex = Duplex::new(512);
+            assert_eq!(
+                duplex.duplexing(input.as_slice(), 256),
+                keccak_bytes(256, input)
+            );
+        }
+
+        let mut duplex = Duplex::new(1024);
+        assert_eq!(duplex.duplexing("abc", 512), keccak512_bytes("abc"));
+    }
+
+    #[test]
+    fn test_multiple_calls() {
+        let mut duplex = Duplex::new(512);
+        let first = duplex.duplexing("first", 256);
+        let second = duplex.duplexing("second", 256);
+        assert_ne!(first, second);
+
+        // The second call gives the output of the sponge on the padded first block followed by the second input.
+        let mut m = b"first".to_vec();
+        m.push(0x01);
+        m.resize(136, 0x00);
+        m[135] ^= 0x80;
+        m.extend_from_slice(b"second");
+        assert_eq!(second, keccak_bytes(256, m));
+
+        // The output can be a full block, which is longer than the output of Keccak-256.
+        assert_eq!(duplex.duplexing("", 136 * 8).len(), 136);
+    }
+
+    #[test]
+    fn test_try_duplexing() {
+        let mut duplex = Duplex::new(512);
+        assert_eq!(
+            duplex.try_duplexing(vec![0; 136], 256),
+            Err(Error::InvalidDuplexInput(136))
+        );
+        assert_eq!(
+            duplex.try_duplexing("", 137 * 8),
+            Err(Error::InvalidDuplexOutput(137 * 8))
+        );
+        assert_eq!(
+            duplex.try_duplexing("", 100),
+            Err(Error::InvalidOutputLength(100))
+        );
+        assert_eq!(
+            Duplex::try_new(100).err(),
+            Some(Error::InvalidSpongeCapacity(100))
+        );
+    }
+}
@@ -0,0 +1,243 @@
+use std::fmt;
+
+/// The errors returned by the fallible (`try_`) versions of the hashing functions when they are given invalid parameters. New variants can be added when new functions are added, so matches on it need a wildcard arm.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum Error {
+    /// The size of a SHA-3 hash has to be one of the standard sizes 224, 256, 384 or 512.
+    InvalidSize(usize),
+    /// The capacity of SHAKE has to be one of the standard sizes 128 or 256.
+    InvalidCapacity(usize),
+    /// The length of the output in bits has to be divisible by 8.
+    InvalidOutputLength(usize),
+    /// The length of a message in bits is larger than the input it is taken from.
+    InvalidBitLength(usize),
+    /// The block size of ParallelHash has to be at least one byte.
+    InvalidBlockSize(usize),
+    /// The domain separation byte of TurboSHAKE has to be between 0x01 and 0x7F.
+    InvalidDomain(u8),
+    /// The capacity of a Keccak sponge has to leave a rate (1600 - capacity) which is a positive multiple of the lane size of 64 bits.
+    InvalidSpongeCapacity(usize),
+    /// The suffix bits of a Keccak sponge have to fit in their length, which can be at most 7 bits.
+    InvalidSuffix(u8, usize),
+    /// The permutation can do at most 24 rounds.
+    InvalidRounds(usize),
+    /// The input of a duplexing call has to be shorter than the rate, to leave room for the padding.
+    InvalidDuplexInput(usize),
+    /// The output of a duplexing call in bits can be at most the rate.
+    InvalidDuplexOutput(usize),
+    /// The tag of an authenticated message does not match, so the message or associated data has been modified or the key is wrong.
+    InvalidTag,
+    /// A tag has to be at least 32 bits, and a truncated tag can not be longer than the full tag.
+    InvalidTagSize(usize),
+    /// The length of a derived key is too long for the key derivation function.
+    InvalidKeyLength(usize),
+    /// The number of iterations of PBKDF2 has to be at least 1.
+    InvalidIterations(u32),
+    /// A hex string has to consist of pairs of hex digits.
+    InvalidHex(String),
+    /// The entropy input of a DRBG has to be at least as long as the security strength.
+    InvalidEntropyLength(usize),
+    /// A DRBG request can be at most 2^16 bytes.
+    InvalidRequestLength(usize),
+    /// A DRBG has to be reseeded with new entropy input, because the reseed interval has passed or it needs prediction resistance.
+    ReseedRequired,
+    /// A parsed hash value has to have the size of the HashValue type.
+    InvalidHashLength(usize, usize),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Error::InvalidSize(size) => write!(
+                f,
+                "Invalid size {}, has to be one of 224, 256, 384 or 512",
+                size
+            ),
+            Error::InvalidCapacity(capacity) => write!(
+                f,
+                "Invalid capacity {}, has to be one of 128 or 256",
+                capacity
+            ),
+            Error::InvalidOutputLength(size) => write!(
+                f,
+                "Invalid size {}, the output length in bits has to be divisible by 8",
+                size
+            ),
+            Error::InvalidBitLength(bits) => write!(
+                f,
+                "Invalid bit length {}, the message is longer than the input",
+                bits
+            ),
+            Error::InvalidBlockSize(block_size) => write!(
+                f,
+                "Invalid block size {}, has to be at least one byte",
+                block_size
+            ),
+            Error::InvalidDomain(domain) => write!(
+                f,
+                "Invalid domain separation byte {:#04x}, has to be between 0x01 and 0x7f",
+                domain
+            ),
+            Error::InvalidSpongeCapacity(capacity) => write!(
+                f,
+                "Invalid capacity {}, the rate (1600 - capacity) has to be a positive multiple of 64",
+                capacity
+            ),
+            Error::InvalidSuffix(bits, len) => write!(
+                f,
+                "Invalid suffix {:#b} of {} bits, has to fit in its length of at most 7 bits",
+                bits, len
+            ),
+            Error::InvalidRounds(rounds) => write!(
+                f,
+                "Invalid number of rounds {}, has to be at most 24",
+                rounds
+            ),
+            Error::InvalidDuplexInput(length) => write!(
+                f,
+                "Invalid duplex input of {} bytes, has to be shorter than the rate",
+                length
+            ),
+            Error::InvalidDuplexOutput(size) => write!(
+                f,
+                "Invalid duplex output size {}, can be at most the rate",
+                size
+            ),
+            Error::InvalidTag => write!(
+                f,
+                "Invalid tag, the message is not authentic"
+            ),
+            Error::InvalidTagSize(size) => write!(
+                f,
+                "Invalid tag size {}, has to be at least 32 bits and at most the size of the full tag",
+                size
+            ),
+            Error::InvalidKeyLength(length) => write!(
+                f,
+                "Invalid key length {} bytes, is too long for the key derivation function",
+                length
+            ),
+            Error::InvalidIterations(iterations) => write!(
+                f,
+                "Invalid number of iterations {}, has to be at least 1",
+                iterations
+            ),
+            Error::InvalidHex(hex) => write!(
+                f,
+                "Invalid hex string {:?}, has to be an even number of hex digits",
+                hex
+            ),
+            Error::InvalidEntropyLength(length) => write!(
+                f,
+                "Invalid entropy input of {} bytes, has to be at least the security strength",
+                length
+            ),
+            Error::InvalidRequestLength(length) => write!(
+                f,
+                "Invalid request of {} bytes, can be at most 65536 bytes",
+                length
+            ),
+            Error::ReseedRequired => write!(
+                f,
+                "The DRBG has to be reseeded with new entropy input"
+            ),
+            Error::InvalidHashLength(length, expected) => write!(
+                f,
+                "Invalid hash value length {} bytes, has to be {} bytes",
+                length, expected
+            ),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_display() {
+        assert_eq!(
+            Error::InvalidSize(100).to_string(),
+            "Invalid size 100, has to be one of 224, 256, 384 or 512"
+        );
+        assert_eq!(
+            Error::InvalidCapacity(512).to_string(),
+            "Invalid capacity 512, has to be one of 128 or 256"
+        );
+        assert_eq!(
+            Error::InvalidOutputLength(7).to_string(),
+            "Invalid size 7, the output length in bits has to be divisible by 8"
+        );
+        assert_eq!(
+            Error::InvalidBitLength(9).to_string(),
+            "Invalid bit length 9, the message is longer than the input"
+        );
+        assert_eq!(
+            Error::InvalidBlockSize(0).to_string(),
+            "Invalid block size 0, has to be at least one byte"
+        );
+        assert_eq!(
+            Error::InvalidDomain(0x80).to_string(),
+            "Invalid domain separation byte 0x80, has to be between 0x01 and 0x7f"
+        );
+        assert_eq!(
+            Error::InvalidSpongeCapacity(100).to_string(),
+            "Invalid capacity 100, the rate (1600 - capacity) has to be a positive multiple of 64"
+        );
+        assert_eq!(
+            Error::InvalidSuffix(0b101, 2).to_string(),
+            "Invalid suffix 0b101 of 2 bits, has to fit in its length of at most 7 bits"
+        );
+        assert_eq!(
+            Error::InvalidRounds(25).to_string(),
+            "Invalid number of rounds 25, has to be at most 24"
+        );
+        assert_eq!(
+            Error::InvalidDuplexInput(136).to_string(),
+            "Invalid duplex input of 136 bytes, has to be shorter than the rate"
+        );
+        assert_eq!(
+            Error::InvalidDuplexOutput(1096).to_string(),
+            "Invalid duplex output size 1096, can be at most the rate"
+        );
+        assert_eq!(
+            Error::InvalidTag.to_string(),
+            "Invalid tag, the message is not authentic"
+        );
+        assert_eq!(
+            Error::InvalidTagSize(264).to_string(),
+            "Invalid tag size 264, has to be at least 32 bits and at most the size of the full tag"
+        );
+        assert_eq!(
+            Error::InvalidKeyLength(8161).to_string(),
+            "Invalid key length 8161 bytes, is too long for the key derivation function"
+        );
+        assert_eq!(
+            Error::InvalidIterations(0).to_string(),
+            "Invalid number of iterations 0, has to be at least 1"
+        );
+        assert_eq!(
+            Error::InvalidHex("abc".to_string()).to_string(),
+            "Invalid hex string \"abc\", has to be an even number of hex digits"
+        );
+        assert_eq!(
+            Error::InvalidEntropyLength(15).to_string(),
+            "Invalid entropy input of 15 bytes, has to be at least the security strength"
+        );
+        assert_eq!(
+            Error::InvalidRequestLength(65537).to_string(),
+            "Invalid request of 65537 bytes, can be at most 65536 bytes"
+        );
+        assert_eq!(
+            Error::ReseedRequired.to_string(),
+            "The DRBG has to be reseeded with new entropy input"
+        );
+        assert_eq!(
+            Error::InvalidHashLength(2, 32).to_string(),
+            "Invalid hash value length 2 bytes, has to be 32 bytes"
+        );
+    }
+}
@@ -0,0 +1,285 @@
+use crate::error::Error;
+use crate::input::Input;
+use crate::keccak::keccak_bytes;
+use crate::sha3::sha3_bytes;
+use crate::shake::shake_bytes;
+use crate::utils::{constant_time_eq, hex_to_bytes};
+use std::fmt;
+use std::marker::PhantomData;
+use std::str::FromStr;
+
+/// The trait implemented by the marker types which record the algorithm of a HashValue in its type, so hashes of different algorithms can not be mixed up.
+pub trait Algorithm {
+    /// The name of the algorithm, which is used when the hash value is debug printed.
+    const NAME: &'static str;
+}
+
+/// A hash value of N bytes computed with the algorithm A. The bytes can be shown as hex with `Display`, `LowerHex` and `UpperHex` and parsed back from hex with `FromStr`. Comparing two hash values takes the same time no matter where they differ, so they can be used to check secret values like tags without leaking timing information.
+pub struct HashValue<const N: usize, A: Algorithm> {
+    bytes: [u8; N],
+    algorithm: PhantomData<A>,
+}
+
+impl<const N: usize, A: Algorithm> HashValue<N, A> {
+    /// Create a hash value from bytes which were computed with the algorithm A.
+    pub fn from_bytes(bytes: [u8; N]) -> HashValue<N, A> {
+        HashValue {
+            bytes,
+            algorithm: PhantomData,
+        }
+    }
+
+    /// The bytes of the hash value.
+    pub fn as_bytes(&self) -> &[u8; N] {
+        &self.bytes
+    }
+
+    /// Consume the hash value and return its bytes.
+    pub fn into_bytes(self) -> [u8; N] {
+        self.bytes
+    }
+}
+
+/// Implement hash for the HashValue types of a fixed size algorithm, using the function which returns the bytes of the hash.
+macro_rules! fixed_hash_value {
+    ($(#[$doc:meta])* $alg:ident, $alias:ident, $name:literal, $function:ident, $size:literal) => {
+        $(#[$doc])*
+        pub enum $alg {}
+
+        impl Algorithm for $alg {
+            const NAME: &'static str = $name;
+        }
+
+        #[doc = concat!("A ", $name, " hash value.")]
+        pub type $alias = HashValue<{ $size / 8 }, $alg>;
+
+        impl $alias {
+            #[doc = concat!("The ", $name, " hash of the input, which can be of any type that implements the Input trait.")]
+            pub fn hash<T: Input>(input: T) -> $alias {
+                HashValue::from_bytes($function($size, input).try_into().unwrap())
+            }
+        }
+    };
+}
+
+/// Implement hash for the HashValue types of SHAKE, where the size of the output is the size of the hash value.
+macro_rules! xof_hash_value {
+    ($(#[$doc:meta])* $alg:ident, $alias:ident, $name:literal, $capacity:literal) => {
+        $(#[$doc])*
+        pub enum $alg {}
+
+        impl Algorithm for $alg {
+            const NAME: &'static str = $name;
+        }
+
+        #[doc = concat!("A ", $name, " output of N bytes.")]
+        pub type $alias<const N: usize> = HashValue<N, $alg>;
+
+        impl<const N: usize> $alias<N> {
+            #[doc = concat!("The ", $name, " output of N bytes for the input, which can be of any type that implements the Input trait.")]
+            pub fn hash<T: Input>(input: T) -> $alias<N> {
+                HashValue::from_bytes(shake_bytes($capacity, input, N * 8).try_into().unwrap())
+            }
+        }
+    };
+}
+
+fixed_hash_value!(
+    /// The marker type for hash values computed with SHA3-224.
+    Sha3_224Algorithm, Sha3_224Digest, "SHA3-224", sha3_bytes, 224
+);
+fixed_hash_value!(
+    /// The marker type for hash values computed with SHA3-256.
+    Sha3_256Algorithm, Sha3_256Digest, "SHA3-256", sha3_bytes, 256
+);
+fixed_hash_value!(
+    /// The marker type for hash values computed with SHA3-384.
+    Sha3_384Algorithm, Sha3_384Digest, "SHA3-384", sha3_bytes, 384
+);
+fixed_hash_value!(
+    /// The marker type for hash values computed with SHA3-512.
+    Sha3_512Algorithm, Sha3_512Digest, "SHA3-512", sha3_bytes, 512
+);
+fixed_hash_value!(
+    /// The marker type for hash values computed with Keccak-224 with the original padding.
+    Keccak224Algorithm, Keccak224Digest, "Keccak-224", keccak_bytes, 224
+);
+fixed_hash_value!(
+    /// The marker type for hash values computed with Keccak-256 with the original padding.
+    Keccak256Algorithm, Keccak256Digest, "Keccak-256", keccak_bytes, 256
+);
+fixed_hash_value!(
+    /// The marker type for hash values computed with Keccak-384 with the original padding.
+    Keccak384Algorithm, Keccak384Digest, "Keccak-384", keccak_bytes, 384
+);
+fixed_hash_value!(
+    /// The marker type for hash values computed with Keccak-512 with the original padding.
+    Keccak512Algorithm, Keccak512Digest, "Keccak-512", keccak_bytes, 512
+);
+xof_hash_value!(
+    /// The marker type for outputs of SHAKE128.
+    Shake128Algorithm, Shake128Digest, "SHAKE128", 128
+);
+xof_hash_value!(
+    /// The marker type for outputs of SHAKE256.
+    Shake256Algorithm, Shake256Digest, "SHAKE256", 256
+);
+
+// Clone and Copy are implemented by hand, since deriving them would also require the marker type to implement them.
+impl<const N: usize, A: Algorithm> Clone for HashValue<N, A> {
+    fn clone(&self) -> HashValue<N, A> {
+        *self
+    }
+}
+
+impl<const N: usize, A: Algorithm> Copy for HashValue<N, A> {}
+
+impl<const N: usize, A: Algorithm> PartialEq for HashValue<N, A> {
+    fn eq(&self, other: &HashValue<N, A>) -> bool {
+        constant_time_eq(&self.bytes, &other.bytes)
+    }
+}
+
+impl<const N: usize, A: Algorithm> Eq for HashValue<N, A> {}
+
+impl<const N: usize, A: Algorithm> From<[u8; N]> for HashValue<N, A> {
+    fn from(bytes: [u8; N]) -> HashValue<N, A> {
+        HashValue::from_bytes(bytes)
+    }
+}
+
+impl<const N: usize, A: Algorithm> AsRef<[u8]> for HashValue<N, A> {
+    fn as_ref(&self) -> &[u8] {
+        &self.bytes
+    }
+}
+
+impl<const N: usize, A: Algorithm> fmt::LowerHex for HashValue<N, A> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if f.alternate() {
+            write!(f, "0x")?;
+        }
+
+        for byte in &self.bytes {
+            write!(f, "{:02x}", byte)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl<const N: usize, A: Algorithm> fmt::UpperHex for HashValue<N, A> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if f.alternate() {
+            write!(f, "0x")?;
+        }
+
+        for byte in &self.bytes {
+            write!(f, "{:02X}", byte)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// The hash value is displayed as lowercase hex, which is the same as the hex strings returned by the hashing functions.
+impl<const N: usize, A: Algorithm> fmt::Display for HashValue<N, A> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::LowerHex::fmt(self, f)
+    }
+}
+
+impl<const N: usize, A: Algorithm> fmt::Debug for HashValue<N, A> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}({:x})", A::NAME, self)
+    }
+}
+
+/// Parse a hash value from hex with either lowercase or uppercase digits, like the hex strings returned by the hashing functions. Returns an error if the string is not valid hex or does not have the length of the hash value.
+impl<const N: usize, A: Algorithm> FromStr for HashValue<N, A> {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<HashValue<N, A>, Error> {
+        let bytes = hex_to_bytes(s)?;
+        let length = bytes.len();
+
+        bytes
+            .try_into()
+            .map(HashValue::from_bytes)
+            .map_err(|_| Error::InvalidHashLength(length, N))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::keccak::keccak256;
+    use crate::sha3::sha3_256;
+    use crate::shake::shake128;
+
+    #[test]
+    fn test_hash() {
+        let input = "The quick brown fox jumps over the lazy dog";
+        assert_eq!(Sha3_256Digest::hash(input).to_string(), sha3_256(input));
+        assert_eq!(Keccak256Digest::hash(input).to_string(), keccak256(input));
+        assert_eq!(
+            Shake128Digest::<100>::hash(input).to_string(),
+            shake128(input, 800)
+        );
+        assert_eq!(Sha3_224Digest::hash("").as_bytes().len(), 28);
+        assert_eq!(Sha3_512Digest::hash("").into_bytes().len(), 64);
+    }
+
+    #[test]
+    fn test_format() {
+        let hash = Sha3_224Digest::hash("");
+        assert_eq!(
+            format!("{}", hash),
+            "6b4e03423667dbb73b6e15454f0eb1abd4597f9a1b078e3f5b5a6bc7"
+        );
+        assert_eq!(
+            format!("{:X}", hash),
+            "6B4E03423667DBB73B6E15454F0EB1ABD4597F9A1B078E3F5B5A6BC7"
+        );
+        assert_eq!(
+            format!("{:#x}", hash),
+            "0x6b4e03423667dbb73b6e15454f0eb1abd4597f9a1b078e3f5b5a6bc7"
+        );
+        assert_eq!(
+            format!("{:?}", hash),
+            "SHA3-224(6b4e03423667dbb73b6e15454f0eb1abd4597f9a1b078e3f5b5a6bc7)"
+        );
+    }
+
+    #[test]
+    fn test_parse() {
+        let hash = Sha3_256Digest::hash("abc");
+        assert_eq!(hash.to_string().parse(), Ok(hash));
+        assert_eq!(format!("{:X}", hash).parse(), Ok(hash));
+        assert_eq!(hash.as_ref(), sha3_bytes(256, "abc"));
+
+        assert_eq!(
+            "abcd".parse::<Sha3_256Digest>(),
+            Err(Error::InvalidHashLength(2, 32))
+        );
+        assert_eq!(
+            "xyz".parse::<Keccak512Digest>(),
+            Err(Error::InvalidHex("xyz".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_eq() {
+        let a = Sha3_256Digest::hash("a");
+        let b = Sha3_256Digest::hash("b");
+        assert_eq!(a, Sha3_256Digest::from(*a.as_bytes()));
+        assert_ne!(a, b);
+
+        // Flipping any single bit makes the hash values different.
+        for i in 0..32 * 8 {
+            let mut bytes = a.into_bytes();
+            bytes[i / 8] ^= 1 << (i % 8);
+            assert_ne!(a, Sha3_256Digest::from_bytes(bytes));
+        }
+    }
+}
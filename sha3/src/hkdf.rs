@@ -0,0 +1,128 @@
+use crate::error::Error;
+use crate::hmac::{try_hmac_bytes, Hmac};
+use crate::input::Input;
+use crate::sha3::check_size;
+
+/// The HKDF key derivation function as defined in RFC 5869 with HMAC-SHA3, which is HKDF-Extract followed by HKDF-Expand. The size is the size of the SHA-3 hash (224, 256, 384 or 512), the salt, input keying material and info can be of any type that implements the Input trait, and the length is the number of bytes of output keying material. Returns an error if the size is invalid or the length is more than 255 times the size of the hash in bytes.
+pub fn hkdf<S: Input, K: Input, I: Input>(
+    size: usize,
+    salt: S,
+    ikm: K,
+    info: I,
+    length: usize,
+) -> Result<Vec<u8>, Error> {
+    let prk = hkdf_extract(size, salt, ikm)?;
+    hkdf_expand(size, prk, info, length)
+}
+
+/// The HKDF-Extract function from section 2.2 of RFC 5869, which concentrates the input keying material into a pseudorandom key with the same size as the hash. An empty salt is the same as a salt of zeros, since HMAC pads the key with zeros anyway. Returns an error if the size is invalid.
+pub fn hkdf_extract<S: Input, K: Input>(size: usize, salt: S, ikm: K) -> Result<Vec<u8>, Error> {
+    // The salt is the key of HMAC and the input keying material is the message.
+    try_hmac_bytes(size, salt, ikm)
+}
+
+/// The HKDF-Expand function from section 2.3 of RFC 5869, which expands the pseudorandom key to length bytes of output keying material bound to the info. Returns an error if the size is invalid or the length is more than 255 times the size of the hash in bytes.
+pub fn hkdf_expand<P: Input, I: Input>(
+    size: usize,
+    prk: P,
+    info: I,
+    length: usize,
+) -> Result<Vec<u8>, Error> {
+    check_size(size)?;
+
+    // The counter is a single byte, so there can be at most 255 blocks of output.
+    if length > 255 * size / 8 {
+        return Err(Error::InvalidKeyLength(length));
+    }
+
+    let hmac = Hmac::try_new(size, prk)?;
+    let info = info.convert();
+    let mut okm = Vec::with_capacity(length);
+    let mut block = Vec::new();
+
+    // T(i) = HMAC-Hash(PRK, T(i - 1) | info | i), where T(0) is empty.
+    for i in 1..=length.div_ceil(size / 8) {
+        let mut next = hmac.clone();
+        next.update(block);
+        next.update(info);
+        next.update([i as u8].as_slice());
+        block = next.finalize_bytes();
+        okm.extend_from_slice(&block);
+    }
+
+    okm.truncate(length);
+    Ok(okm)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::bytes_to_hex;
+
+    #[test]
+    fn test_hkdf_sha3_256() {
+        // The inputs of test case 1 of RFC 5869, which only has vectors for SHA-2. The expected outputs were computed with the hmac and hashlib modules of Python.
+        let ikm = [0x0B; 22];
+        let salt: Vec<u8> = (0x00..=0x0C).collect();
+        let info: Vec<u8> = (0xF0..=0xF9).collect();
+
+        let prk = hkdf_extract(256, salt.as_slice(), ikm.as_slice()).unwrap();
+        assert_eq!(
+            bytes_to_hex(&prk),
+            "7d4194836f7a113a44677abc825640ade07af1c1d69a9a4b109b280a8fe54ef0"
+        );
+        assert_eq!(
+            bytes_to_hex(&hkdf_expand(256, prk, info.as_slice(), 42).unwrap()),
+            "0c5160501d65021deaf2c14f5abce04c5bd2635abceeba61c2edb6e8ed72674900557728f2c9f2c4c179"
+        );
+
+        // The inputs of test case 2, with longer inputs and output.
+        let ikm: Vec<u8> = (0x00..=0x4F).collect();
+        let salt: Vec<u8> = (0x60..=0xAF).collect();
+        let info: Vec<u8> = (0xB0..=0xFF).collect();
+        assert_eq!(
+            bytes_to_hex(&hkdf(256, salt, ikm, info, 82).unwrap()),
+            "3dc251e66c75da6560405ec5ac10e17d851eedfbfdc13feafbec16964c25d021bd971465a3e9c615f27769019e3f0407d84986fb0ba24e729c99834624baa21cb623dc0098f430d52e18bbdf694df4edd8b2"
+        );
+    }
+
+    #[test]
+    fn test_hkdf_sha3_512() {
+        // The inputs of test case 3 of RFC 5869, with an empty salt and info.
+        let ikm = [0x0B; 22];
+        assert_eq!(
+            bytes_to_hex(&hkdf_extract(512, "", ikm.as_slice()).unwrap()),
+            "37a48c72dce8c34bf1a08356c929133ea60a20c6c2eb3ce26d2c3ce6b0e2385572e82fc77418ace2f6df0419eacafc847fdf283b0324163d7d88265a8e7e4992"
+        );
+        assert_eq!(
+            bytes_to_hex(&hkdf(512, "", ikm.as_slice(), "", 42).unwrap()),
+            "38bd71e45b397b775b563365a33258a6fd83abc1e86acf042f0723c2b68ebf073a75c34c69328835ee4c"
+        );
+    }
+
+    #[test]
+    fn test_hkdf_expand_prefix() {
+        // Shorter outputs are a prefix of longer outputs with the same inputs.
+        let long = hkdf(384, "salt", "ikm", "info", 255 * 48).unwrap();
+        let short = hkdf(384, "salt", "ikm", "info", 50).unwrap();
+        assert_eq!(long.len(), 255 * 48);
+        assert_eq!(long[..50], short);
+        assert_eq!(hkdf(384, "salt", "ikm", "info", 0), Ok(Vec::new()));
+    }
+
+    #[test]
+    fn test_hkdf_errors() {
+        assert_eq!(
+            hkdf(256, "salt", "ikm", "info", 255 * 32 + 1),
+            Err(Error::InvalidKeyLength(255 * 32 + 1))
+        );
+        assert_eq!(
+            hkdf_expand(224, [0; 28].as_slice(), "", 255 * 28 + 1),
+            Err(Error::InvalidKeyLength(255 * 28 + 1))
+        );
+        assert_eq!(
+            hkdf(160, "salt", "ikm", "info", 32),
+            Err(Error::InvalidSize(160))
+        );
+    }
+}
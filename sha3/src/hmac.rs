@@ -0,0 +1,296 @@
+use crate::error::Error;
+use crate::input::Input;
+use crate::sha3::{check_size, Sha3Hasher};
+use crate::shake::check_output_length;
+use crate::utils::{bytes_to_hex, constant_time_eq};
+
+/// The shortest tag in bits which is accepted by verify, since section 5.3.4 of SP 800-107, which section 5 of FIPS 198-1 refers to, requires truncated tags to be at least 32 bits.
+const MIN_TAG_SIZE: usize = 32;
+
+/// The HMAC message authentication code as defined in FIPS 198-1 with SHA-3 as the hash function. The size is the size of the SHA-3 hash (224, 256, 384 or 512), which is also the length of the tag in bits, and the key and input can be of any type that implements the Input trait. Panics if the size is invalid.
+pub fn hmac<K: Input, T: Input>(size: usize, key: K, input: T) -> String {
+    bytes_to_hex(&hmac_bytes(size, key, input))
+}
+
+/// The HMAC function returning the raw bytes of the tag instead of a hex string. The parameters are the same as for the hmac function.
+pub fn hmac_bytes<K: Input, T: Input>(size: usize, key: K, input: T) -> Vec<u8> {
+    try_hmac_bytes(size, key, input).unwrap_or_else(|e| panic!("{}", e))
+}
+
+/// Fallible version of the hmac_bytes function, which returns an error instead of panicking when the size is invalid.
+pub fn try_hmac_bytes<K: Input, T: Input>(size: usize, key: K, input: T) -> Result<Vec<u8>, Error> {
+    let mut hmac = Hmac::try_new(size, key)?;
+    hmac.update(input);
+    Ok(hmac.finalize_bytes())
+}
+
+/// Shortcut for the HMAC-SHA3-224 function, the key and input can be of any type that implements the Input trait.
+pub fn hmac_sha3_224<K: Input, T: Input>(key: K, input: T) -> String {
+    hmac(224, key, input)
+}
+
+/// Shortcut for the HMAC-SHA3-256 function, the key and input can be of any type that implements the Input trait.
+pub fn hmac_sha3_256<K: Input, T: Input>(key: K, input: T) -> String {
+    hmac(256, key, input)
+}
+
+/// Shortcut for the HMAC-SHA3-384 function, the key and input can be of any type that implements the Input trait.
+pub fn hmac_sha3_384<K: Input, T: Input>(key: K, input: T) -> String {
+    hmac(384, key, input)
+}
+
+/// Shortcut for the HMAC-SHA3-512 function, the key and input can be of any type that implements the Input trait.
+pub fn hmac_sha3_512<K: Input, T: Input>(key: K, input: T) -> String {
+    hmac(512, key, input)
+}
+
+/// Incremental version of HMAC for input which is not available all at once. The key is absorbed into both the inner and outer hash when the Hmac is created, so it can be cloned to authenticate several messages with the same key.
+#[derive(Clone)]
+pub struct Hmac {
+    inner: Sha3Hasher,
+    outer: Sha3Hasher,
+    size: usize,
+}
+
+impl Hmac {
+    /// Create a new HMAC with the given SHA-3 size (224, 256, 384 or 512) and key. Panics if the size is invalid.
+    pub fn new<K: Input>(size: usize, key: K) -> Hmac {
+        Hmac::try_new(size, key).unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    /// Fallible version of new, which returns an error instead of panicking when the size is invalid.
+    pub fn try_new<K: Input>(size: usize, key: K) -> Result<Hmac, Error> {
+        check_size(size)?;
+
+        // The block size B of HMAC is the rate of the SHA-3 function in bytes, so 144, 136, 104 or 72.
+        let block_size = (1600 - size * 2) / 8;
+
+        // Step 1-3 of section 4 of FIPS 198-1: keys longer than a block are hashed first, and the key is padded with zeros to a full block.
+        let key = key.convert();
+        let mut k0 = if key.len() > block_size {
+            let mut hasher = Sha3Hasher::new(size);
+            hasher.update(key);
+            hasher.finalize_bytes()
+        } else {
+            key.to_vec()
+        };
+        k0.resize(block_size, 0x00);
+
+        // Step 4 and 7: XOR the key with ipad and opad, and absorb it in front of the inner and outer input.
+        let mut inner = Sha3Hasher::new(size);
+        inner.update(k0.iter().map(|byte| byte ^ 0x36).collect::<Vec<u8>>());
+        let mut outer = Sha3Hasher::new(size);
+        outer.update(k0.iter().map(|byte| byte ^ 0x5C).collect::<Vec<u8>>());
+
+        Ok(Hmac { inner, outer, size })
+    }
+
+    /// Add more input to the HMAC, the input can be of any type that implements the Input trait.
+    pub fn update<T: Input>(&mut self, input: T) {
+        self.inner.update(input);
+    }
+
+    /// Consume the HMAC and return the tag of all the input given so far.
+    pub fn finalize(self) -> String {
+        bytes_to_hex(&self.finalize_bytes())
+    }
+
+    /// Consume the HMAC and return the raw bytes of the tag of all the input given so far.
+    pub fn finalize_bytes(mut self) -> Vec<u8> {
+        // Step 5-9 of section 4 of FIPS 198-1: the outer hash is taken of the inner hash.
+        self.outer.update(self.inner.finalize_bytes());
+        self.outer.finalize_bytes()
+    }
+
+    /// Consume the HMAC and return the leftmost size bits of the tag as described in section 5 of FIPS 198-1. Panics if the size is invalid.
+    pub fn finalize_truncated(self, size: usize) -> Vec<u8> {
+        self.try_finalize_truncated(size)
+            .unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    /// Fallible version of finalize_truncated, which returns an error instead of panicking when the size is not divisible by 8 or is longer than the tag.
+    pub fn try_finalize_truncated(self, size: usize) -> Result<Vec<u8>, Error> {
+        check_output_length(size)?;
+        if size > self.size {
+            return Err(Error::InvalidTagSize(size));
+        }
+
+        let mut tag = self.finalize_bytes();
+        tag.truncate(size / 8);
+        Ok(tag)
+    }
+
+    /// Consume the HMAC and check whether the tag is correct for the input given so far. The tag size is the expected size of the tag in bits, which is the size of the hash or a truncated size of at least 32 bits (has to be a number divisible by 8). The comparison is done in constant time, and a tag which does not have the expected size is never correct.
+    pub fn verify(self, tag: &[u8], tag_size: usize) -> bool {
+        // The size is not taken from the tag itself, since a short tag made up by an attacker would then be easy to guess.
+        if tag_size < MIN_TAG_SIZE || tag.len() * 8 != tag_size {
+            return false;
+        }
+
+        match self.try_finalize_truncated(tag_size) {
+            Ok(expected) => constant_time_eq(&expected, tag),
+            Err(_) => false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn key(length: u8) -> Vec<u8> {
+        (0..length).collect()
+    }
+
+    #[test]
+    fn test_hmac_sha3_224() {
+        // The examples of NIST for HMAC-SHA3-224, with keys shorter than, equal to and longer than the block size of 144 bytes.
+        assert_eq!(
+            hmac_sha3_224(key(28), "Sample message for keylen<blocklen"),
+            "332cfd59347fdb8e576e77260be4aba2d6dc53117b3bfb52c6d18c04"
+        );
+        assert_eq!(
+            hmac_sha3_224(key(144), "Sample message for keylen=blocklen"),
+            "d8b733bcf66c644a12323d564e24dcf3fc75f231f3b67968359100c7"
+        );
+        assert_eq!(
+            hmac_sha3_224(key(172), "Sample message for keylen>blocklen"),
+            "078695eecc227c636ad31d063a15dd05a7e819a66ec6d8de1e193e59"
+        );
+    }
+
+    #[test]
+    fn test_hmac_sha3_256() {
+        // The examples of NIST for HMAC-SHA3-256 with a block size of 136 bytes.
+        assert_eq!(
+            hmac_sha3_256(key(32), "Sample message for keylen<blocklen"),
+            "4fe8e202c4f058e8dddc23d8c34e467343e23555e24fc2f025d598f558f67205"
+        );
+        assert_eq!(
+            hmac_sha3_256(key(136), "Sample message for keylen=blocklen"),
+            "68b94e2e538a9be4103bebb5aa016d47961d4d1aa906061313b557f8af2c3faa"
+        );
+        assert_eq!(
+            hmac_sha3_256(key(168), "Sample message for keylen>blocklen"),
+            "9bcf2c238e235c3ce88404e813bd2f3a97185ac6f238c63d6229a00b07974258"
+        );
+    }
+
+    #[test]
+    fn test_hmac_sha3_384() {
+        // The examples of NIST for HMAC-SHA3-384 with a block size of 104 bytes.
+        assert_eq!(
+            hmac_sha3_384(key(48), "Sample message for keylen<blocklen"),
+            "d588a3c51f3f2d906e8298c1199aa8ff6296218127f6b38a90b6afe2c5617725bc99987f79b22a557b6520db710b7f42"
+        );
+        assert_eq!(
+            hmac_sha3_384(key(104), "Sample message for keylen=blocklen"),
+            "a27d24b592e8c8cbf6d4ce6fc5bf62d8fc98bf2d486640d9eb8099e24047837f5f3bffbe92dcce90b4ed5b1e7e44fa90"
+        );
+        assert_eq!(
+            hmac_sha3_384(key(152), "Sample message for keylen>blocklen"),
+            "e5ae4c739f455279368ebf36d4f5354c95aa184c899d3870e460ebc288ef1f9470053f73f7c6da2a71bcaec38ce7d6ac"
+        );
+    }
+
+    #[test]
+    fn test_hmac_sha3_512() {
+        // The examples of NIST for HMAC-SHA3-512 with a block size of 72 bytes.
+        assert_eq!(
+            hmac_sha3_512(key(64), "Sample message for keylen<blocklen"),
+            "4efd629d6c71bf86162658f29943b1c308ce27cdfa6db0d9c3ce81763f9cbce5f7ebe9868031db1a8f8eb7b6b95e5c5e3f657a8996c86a2f6527e307f0213196"
+        );
+        assert_eq!(
+            hmac_sha3_512(key(72), "Sample message for keylen=blocklen"),
+            "544e257ea2a3e5ea19a590e6a24b724ce6327757723fe2751b75bf007d80f6b360744bf1b7a88ea585f9765b47911976d3191cf83c039f5ffab0d29cc9d9b6da"
+        );
+        assert_eq!(
+            hmac_sha3_512(key(136), "Sample message for keylen>blocklen"),
+            "5f464f5e5b7848e3885e49b2c385f0694985d0e38966242dc4a5fe3fea4b37d46b65ceced5dcf59438dd840bab22269f0ba7febdb9fcf74602a35666b2a32915"
+        );
+    }
+
+    #[test]
+    fn test_hmac_truncated() {
+        // The examples of NIST with a truncated tag.
+        let message = "Sample message for keylen<blocklen, with truncated tag";
+        for (size, tag) in [
+            (224, "8569c54cbb00a9b78ff1b391b0e5"),
+            (256, "c8dc7148d8c1423aa549105dafdf9cad"),
+            (384, "25f4bf53606e91af79d24a4bb1fd6aecd44414a30c8ebb0a"),
+            (
+                512,
+                "7bb06d859257b25ce73ca700df34c5cbef5c898bac91029e0b27975d4e526a08",
+            ),
+        ] {
+            let mut hmac = Hmac::new(size, key((size / 8) as u8));
+            hmac.update(message);
+            assert_eq!(bytes_to_hex(&hmac.finalize_truncated(tag.len() * 4)), tag);
+        }
+    }
+
+    #[test]
+    fn test_hmac_streaming() {
+        // The first test case of RFC 4231, which is also used for HMAC-SHA3 by several implementations.
+        let mut hmac = Hmac::new(256, [0x0B; 20].as_slice());
+        hmac.update("Hi ");
+        hmac.update("There");
+        assert_eq!(
+            hmac.finalize(),
+            "ba85192310dffa96e2a3a40e69774351140bb7185e1202cdcc917589f95e16bb"
+        );
+
+        let mut split = Hmac::new(512, "key");
+        for chunk in [0u8; 1000].chunks(71) {
+            split.update(chunk);
+        }
+        assert_eq!(
+            split.finalize_bytes(),
+            hmac_bytes(512, "key", [0u8; 1000].as_slice())
+        );
+    }
+
+    #[test]
+    fn test_hmac_verify() {
+        let tag = hmac_bytes(384, "key", "message");
+
+        let mut hmac = Hmac::new(384, "key");
+        hmac.update("message");
+        assert!(hmac.clone().verify(&tag, 384));
+
+        let mut modified = tag.clone();
+        modified[0] ^= 0x01;
+        assert!(!hmac.clone().verify(&modified, 384));
+
+        // A truncated tag is only correct when the truncated size is expected.
+        let truncated = hmac.clone().finalize_truncated(128);
+        assert!(hmac.clone().verify(&truncated, 128));
+        assert!(!hmac.clone().verify(&truncated, 384));
+        assert!(!hmac.clone().verify(&tag, 128));
+
+        // Tags shorter than 32 bits are rejected, even when they are a prefix of the correct tag and have the expected size.
+        assert!(!hmac.clone().verify(&[], 0));
+        assert!(!hmac.clone().verify(&tag[..1], 8));
+        assert!(!hmac.clone().verify(&tag[..3], 24));
+        assert!(hmac.clone().verify(&tag[..4], 32));
+
+        // Tags longer than the hash and sizes which are not whole bytes are never correct.
+        let mut long = tag.clone();
+        long.push(0x00);
+        assert!(!hmac.clone().verify(&long, 392));
+        assert!(!hmac.verify(&tag[..4], 36));
+    }
+
+    #[test]
+    fn test_try_hmac() {
+        assert_eq!(try_hmac_bytes(128, "key", ""), Err(Error::InvalidSize(128)));
+        assert_eq!(
+            Hmac::new(256, "key").try_finalize_truncated(264),
+            Err(Error::InvalidTagSize(264))
+        );
+        assert_eq!(
+            Hmac::new(256, "key").try_finalize_truncated(12),
+            Err(Error::InvalidOutputLength(12))
+        );
+    }
+}
@@ -1,42 +1,88 @@
-/// The trait implemented to handle input for the hashing algorithms.
+use std::fs::File;
+use std::io::{self, Read, Write};
+use std::path::{Path, PathBuf};
+
+/// The trait implemented to handle input for the hashing algorithms. It is implemented for every type which can be seen as a byte slice, like `&str`, `String`, `&[u8]`, `Vec<u8>`, arrays, `Box<[u8]>` and `Cow<[u8]>`. `OsStr` can not be seen as bytes on every platform, so it does not implement the trait and has to be converted with `as_encoded_bytes` first. Input which is read in chunks, like files and readers, uses the StreamInput trait instead.
 pub trait Input {
     /// Convert a type to the wanted input form.
     fn convert(&self) -> &[u8];
 }
 
-impl Input for &str {
+impl<T: AsRef<[u8]> + ?Sized> Input for T {
     fn convert(&self) -> &[u8] {
-        self.as_bytes()
+        self.as_ref()
     }
 }
 
-impl Input for String {
-    fn convert(&self) -> &[u8] {
-        self.as_bytes()
+/// The trait implemented for input which is not available as a single byte slice, like files, readers and iterators of chunks. The input is written to the hasher a chunk at a time, so it never has to be in memory all at once.
+pub trait StreamInput {
+    /// Write all of the input to the writer, which is usually one of the hashers. Returns an error if reading the input fails.
+    fn write_to<W: Write>(self, writer: &mut W) -> io::Result<()>;
+}
+
+impl StreamInput for File {
+    fn write_to<W: Write>(mut self, writer: &mut W) -> io::Result<()> {
+        io::copy(&mut self, writer).map(|_| ())
     }
 }
 
-impl Input for &[u8] {
-    fn convert(&self) -> &[u8] {
-        self
+impl StreamInput for &File {
+    fn write_to<W: Write>(mut self, writer: &mut W) -> io::Result<()> {
+        io::copy(&mut self, writer).map(|_| ())
     }
 }
 
-impl Input for Vec<u8> {
-    fn convert(&self) -> &[u8] {
-        self
+/// The contents of the file at the path.
+impl StreamInput for &Path {
+    fn write_to<W: Write>(self, writer: &mut W) -> io::Result<()> {
+        File::open(self)?.write_to(writer)
     }
 }
 
-impl Input for &String {
-    fn convert(&self) -> &[u8] {
-        self.as_bytes()
+impl StreamInput for &PathBuf {
+    fn write_to<W: Write>(self, writer: &mut W) -> io::Result<()> {
+        self.as_path().write_to(writer)
+    }
+}
+
+impl StreamInput for PathBuf {
+    fn write_to<W: Write>(self, writer: &mut W) -> io::Result<()> {
+        self.as_path().write_to(writer)
+    }
+}
+
+/// Everything read from a reader until the end. A blanket implementation for all readers would overlap with the implementations for paths, so the reader has to be wrapped.
+pub struct FromReader<R: Read>(pub R);
+
+impl<R: Read> StreamInput for FromReader<R> {
+    fn write_to<W: Write>(mut self, writer: &mut W) -> io::Result<()> {
+        io::copy(&mut self.0, writer).map(|_| ())
+    }
+}
+
+/// The concatenation of the chunks of an iterator, where each chunk can be of any type that implements the Input trait.
+pub struct FromChunks<I: IntoIterator>(pub I)
+where
+    I::Item: Input;
+
+impl<I: IntoIterator> StreamInput for FromChunks<I>
+where
+    I::Item: Input,
+{
+    fn write_to<W: Write>(self, writer: &mut W) -> io::Result<()> {
+        for chunk in self.0 {
+            writer.write_all(chunk.convert())?;
+        }
+        Ok(())
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::sha3::{sha3_256, Sha3Hasher};
+    use std::borrow::Cow;
+    use std::fs;
 
     #[test]
     fn test_str_input() {
@@ -72,4 +118,59 @@ mod tests {
         let converted = input.convert();
         assert_eq!(converted, input.as_bytes());
     }
+
+    fn convert<T: Input>(input: T) -> Vec<u8> {
+        input.convert().to_vec()
+    }
+
+    #[test]
+    fn test_as_ref_input() {
+        let bytes = [1, 2, 3];
+        assert_eq!(convert(bytes), [1, 2, 3]);
+        let array_ref: &[u8; 3] = &bytes;
+        assert_eq!(convert(array_ref), [1, 2, 3]);
+        assert_eq!(convert(Box::<[u8]>::from(bytes)), [1, 2, 3]);
+        assert_eq!(convert(Cow::Borrowed(bytes.as_slice())), [1, 2, 3]);
+        let vec = vec![1, 2, 3];
+        let vec_ref: &Vec<u8> = &vec;
+        assert_eq!(convert(vec_ref), [1, 2, 3]);
+        assert_eq!(sha3_256(bytes), sha3_256(bytes.as_slice()));
+    }
+
+    #[test]
+    fn test_stream_input() {
+        let input: Vec<u8> = (0..=255).cycle().take(10000).collect();
+
+        let mut out = Vec::new();
+        FromReader(input.as_slice()).write_to(&mut out).unwrap();
+        assert_eq!(out, input);
+
+        let mut out = Vec::new();
+        FromChunks(input.chunks(7)).write_to(&mut out).unwrap();
+        assert_eq!(out, input);
+
+        let mut out = Vec::new();
+        FromChunks(["The quick brown fox ", "jumps over the lazy dog"])
+            .write_to(&mut out)
+            .unwrap();
+        assert_eq!(out, b"The quick brown fox jumps over the lazy dog");
+    }
+
+    #[test]
+    fn test_file_input() {
+        let path = std::env::temp_dir().join(format!("sha3-input-{}", std::process::id()));
+        let input: Vec<u8> = (0..=255).cycle().take(10000).collect();
+        fs::write(&path, &input).unwrap();
+
+        let mut hasher = Sha3Hasher::new(256);
+        hasher.update_stream(path.as_path()).unwrap();
+        assert_eq!(hasher.finalize(), sha3_256(&input));
+
+        let mut out = Vec::new();
+        File::open(&path).unwrap().write_to(&mut out).unwrap();
+        assert_eq!(out, input);
+
+        fs::remove_file(&path).unwrap();
+        assert!(path.write_to(&mut Vec::new()).is_err());
+    }
 }
@@ -0,0 +1,275 @@
+use crate::error::Error;
+use crate::input::Input;
+use crate::shake::{check_capacity, check_output_length};
+use crate::sponge::{Sponge, XofReader};
+use crate::utils::bytes_to_hex;
+
+/// The size in bytes of the chunks which the input is split into in the tree hashing mode.
+const CHUNK_SIZE: usize = 8192;
+
+/// The KangarooTwelve function as defined in RFC 9861 (KT128 and KT256). Inputs larger than 8192 bytes are split into chunks which are hashed independently with TurboSHAKE, and the chaining values of the chunks are then hashed together. The capacity has to be 128 or 256, the size is the final length of the output in bits (has to be a number divisible by 8) and the customization string can be used to get a different function for each use. Panics if the capacity or size is invalid.
+pub fn kangarootwelve<T: Input, C: Input>(
+    capacity: usize,
+    input: T,
+    size: usize,
+    customization: C,
+) -> String {
+    bytes_to_hex(&kangarootwelve_bytes(capacity, input, size, customization))
+}
+
+/// The KangarooTwelve function returning the raw bytes of the output instead of a hex string. The parameters are the same as for the kangarootwelve function.
+pub fn kangarootwelve_bytes<T: Input, C: Input>(
+    capacity: usize,
+    input: T,
+    size: usize,
+    customization: C,
+) -> Vec<u8> {
+    try_kangarootwelve_bytes(capacity, input, size, customization)
+        .unwrap_or_else(|e| panic!("{}", e))
+}
+
+/// Fallible version of the kangarootwelve_bytes function, which returns an error instead of panicking when the capacity or size is invalid.
+pub fn try_kangarootwelve_bytes<T: Input, C: Input>(
+    capacity: usize,
+    input: T,
+    size: usize,
+    customization: C,
+) -> Result<Vec<u8>, Error> {
+    let mut hasher = KangarooTwelve::try_new(capacity, customization)?;
+    hasher.update(input);
+    hasher.try_finalize_bytes(size)
+}
+
+/// Shortcut for the KT128 function, the parameters are the same as for the kangarootwelve function.
+pub fn kt128<T: Input, C: Input>(input: T, size: usize, customization: C) -> String {
+    kangarootwelve(128, input, size, customization)
+}
+
+/// Shortcut for the KT256 function, the parameters are the same as for the kangarootwelve function.
+pub fn kt256<T: Input, C: Input>(input: T, size: usize, customization: C) -> String {
+    kangarootwelve(256, input, size, customization)
+}
+
+/// The length_encode function from section 3.3 of RFC 9861. The integer is encoded as big endian bytes without leading 0-bytes (so 0 has no bytes) followed by the number of bytes.
+fn length_encode(x: usize) -> Vec<u8> {
+    let mut out: Vec<u8> = x
+        .to_be_bytes()
+        .into_iter()
+        .skip_while(|&byte| byte == 0)
+        .collect();
+    out.push(out.len() as u8);
+    out
+}
+
+/// Incremental version of the KangarooTwelve function for input which is not available all at once. Each chunk is hashed as soon as it is complete, so only one chunk is kept in memory no matter how large the input is.
+#[derive(Clone)]
+pub struct KangarooTwelve {
+    capacity: usize,
+    customization: Vec<u8>,
+    // The final node gets the first chunk directly, followed by the chaining values of the other chunks.
+    final_node: Sponge,
+    // The TurboSHAKE sponge of the current chunk when it is not the first one.
+    leaf: Sponge,
+    // The number of bytes in the current chunk, and the number of chunks after the first one.
+    chunk_len: usize,
+    leaves: usize,
+}
+
+impl KangarooTwelve {
+    /// Create a new hasher with the given capacity (128 or 256) and customization string. Panics if the capacity is invalid.
+    pub fn new<C: Input>(capacity: usize, customization: C) -> KangarooTwelve {
+        KangarooTwelve::try_new(capacity, customization).unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    /// Fallible version of new, which returns an error instead of panicking when the capacity is invalid.
+    pub fn try_new<C: Input>(capacity: usize, customization: C) -> Result<KangarooTwelve, Error> {
+        check_capacity(capacity)?;
+
+        Ok(KangarooTwelve {
+            capacity,
+            customization: customization.convert().to_vec(),
+            final_node: Sponge::with_rounds(capacity * 2, 12),
+            leaf: Sponge::with_rounds(capacity * 2, 12),
+            chunk_len: 0,
+            leaves: 0,
+        })
+    }
+
+    /// Add more input to the hasher, the input can be of any type that implements the Input trait.
+    pub fn update<T: Input>(&mut self, input: T) {
+        self.feed(input.convert());
+    }
+
+    /// Consume the hasher and return the output with the given size in bits (has to be a number divisible by 8).
+    pub fn finalize(self, size: usize) -> String {
+        bytes_to_hex(&self.finalize_bytes(size))
+    }
+
+    /// Consume the hasher and return the raw bytes of the output with the given size in bits (has to be a number divisible by 8).
+    pub fn finalize_bytes(self, size: usize) -> Vec<u8> {
+        self.try_finalize_bytes(size)
+            .unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    /// Fallible version of finalize_bytes, which returns an error instead of panicking when the size is invalid.
+    pub fn try_finalize_bytes(self, size: usize) -> Result<Vec<u8>, Error> {
+        check_output_length(size)?;
+
+        let mut out = vec![0; size / 8];
+        self.finalize_xof().squeeze(&mut out);
+        Ok(out)
+    }
+
+    /// Consume the hasher and return a reader from which any amount of output can be read.
+    pub fn finalize_xof(mut self) -> XofReader {
+        // The message which is split into chunks is S = M || C || length_encode(|C|).
+        let customization = std::mem::take(&mut self.customization);
+        self.feed(&customization);
+        self.feed(&length_encode(customization.len()));
+
+        // If everything fits in one chunk, it is just TurboSHAKE with the domain byte 0x07.
+        if self.leaves == 0 {
+            return self.final_node.finalize_xof(0x07);
+        }
+
+        let cv = self.leaf.finalize(0x0B, self.capacity * 2);
+        self.final_node.update(&cv);
+        self.final_node.update(&length_encode(self.leaves));
+        self.final_node.update(&[0xFF, 0xFF]);
+        self.final_node.finalize_xof(0x06)
+    }
+
+    /// Split the data into chunks. A chunk is only closed when more data arrives, since a message of exactly one chunk is hashed without the tree.
+    fn feed(&mut self, data: &[u8]) {
+        let mut data = data;
+
+        while !data.is_empty() {
+            if self.chunk_len == CHUNK_SIZE {
+                if self.leaves == 0 {
+                    // The first chunk is followed by 0x03 and seven 0-bytes in the final node.
+                    self.final_node.update(&[0x03, 0, 0, 0, 0, 0, 0, 0]);
+                } else {
+                    // The chaining value of the chunk is TurboSHAKE with the domain byte 0x0B and twice the capacity as the output length.
+                    let leaf = std::mem::replace(
+                        &mut self.leaf,
+                        Sponge::with_rounds(self.capacity * 2, 12),
+                    );
+                    self.final_node
+                        .update(&leaf.finalize(0x0B, self.capacity * 2));
+                }
+
+                self.leaves += 1;
+                self.chunk_len = 0;
+            }
+
+            let n = (CHUNK_SIZE - self.chunk_len).min(data.len());
+            if self.leaves == 0 {
+                self.final_node.update(&data[..n]);
+            } else {
+                self.leaf.update(&data[..n]);
+            }
+
+            self.chunk_len += n;
+            data = &data[n..];
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::turboshake::tests::ptn;
+
+    #[test]
+    fn test_length_encode() {
+        assert_eq!(length_encode(0), vec![0x00]);
+        assert_eq!(length_encode(12), vec![0x0C, 0x01]);
+        assert_eq!(length_encode(65538), vec![0x01, 0x00, 0x02, 0x03]);
+    }
+
+    #[test]
+    fn test_kt128() {
+        // Test vectors from section 5 of RFC 9861.
+        assert_eq!(
+            kt128("", 256, ""),
+            "1ac2d450fc3b4205d19da7bfca1b37513c0803577ac7167f06fe2ce1f0ef39e5"
+        );
+        assert_eq!(
+            kt128(ptn(1), 256, ""),
+            "2bda92450e8b147f8a7cb629e784a058efca7cf7d8218e02d345dfaa65244a1f"
+        );
+        assert_eq!(
+            kt128(ptn(17), 256, ""),
+            "6bf75fa2239198db4772e36478f8e19b0f371205f6a9a93a273f51df37122888"
+        );
+        assert_eq!(
+            kt128(ptn(17 * 17), 256, ""),
+            "0c315ebcdedbf61426de7dcf8fb725d1e74675d7f5327a5067f367b108ecb67c"
+        );
+        assert_eq!(
+            kt128(ptn(17 * 17 * 17), 256, ""),
+            "cb552e2ec77d9910701d578b457ddf772c12e322e4ee7fe417f92c758f0d59d0"
+        );
+        assert_eq!(
+            kt128(ptn(17 * 17 * 17 * 17), 256, ""),
+            "8701045e22205345ff4dda05555cbb5c3af1a771c2b89baef37db43d9998b9fe"
+        );
+    }
+
+    #[test]
+    fn test_kt128_customization() {
+        // Test vectors from section 5 of RFC 9861.
+        assert_eq!(
+            kt128("", 256, ptn(1)),
+            "fab658db63e94a246188bf7af69a133045f46ee984c56e3c3328caaf1aa1a583"
+        );
+        assert_eq!(
+            kt128([0xFF].as_slice(), 256, ptn(41)),
+            "d848c5068ced736f4462159b9867fd4c20b808acc3d5bc48e0b06ba0a3762ec4"
+        );
+    }
+
+    #[test]
+    fn test_kt128_chunk_boundary() {
+        // Test vectors from section 5 of RFC 9861 around the size of one chunk.
+        assert_eq!(
+            kt128(ptn(8191), 256, ""),
+            "1b577636f723643e990cc7d6a659837436fd6a103626600eb8301cd1dbe553d6"
+        );
+        assert_eq!(
+            kt128(ptn(8192), 256, ""),
+            "48f256f6772f9edfb6a8b661ec92dc93b95ebd05a08a17b39ae3490870c926c3"
+        );
+        assert_eq!(
+            kt128(ptn(8192), 256, ptn(8189)),
+            "3ed12f70fb05ddb58689510ab3e4d23c6c6033849aa01e1d8c220a297fedcd0b"
+        );
+        assert_eq!(
+            kt128(ptn(8192), 256, ptn(8190)),
+            "6a7c1b6a5cd0d8c9ca943a4a216cc64604559a2ea45f78570a15253d67ba00ae"
+        );
+    }
+
+    #[test]
+    fn test_kt256() {
+        // Test vector from section 5 of RFC 9861.
+        assert_eq!(
+            kt256("", 512, ""),
+            "b23d2e9cea9f4904e02bec06817fc10ce38ce8e93ef4c89e6537076af8646404e3e8b68107b8833a5d30490aa33482353fd4adc7148ecb782855003aaebde4a9"
+        );
+    }
+
+    #[test]
+    fn test_kangarootwelve_streaming() {
+        let input = ptn(3 * CHUNK_SIZE + 100);
+        let expected = kt128(input.as_slice(), 512, "custom");
+
+        for chunk_size in [1000, CHUNK_SIZE, CHUNK_SIZE + 1] {
+            let mut hasher = KangarooTwelve::new(128, "custom");
+            for chunk in input.chunks(chunk_size) {
+                hasher.update(chunk);
+            }
+            assert_eq!(hasher.finalize(512), expected);
+        }
+    }
+}
@@ -0,0 +1,193 @@
+use crate::error::Error;
+use crate::input::{Input, StreamInput};
+use crate::sha3::check_size;
+use crate::sponge::Sponge;
+use crate::utils::{self, bytes_to_hex};
+use std::io;
+
+/// The original Keccak hash function as submitted to the SHA-3 competition, which is used by Ethereum and Solidity (keccak256). It only differs from SHA-3 in the padding, since it was standardized before the domain separator was added in FIPS 202. The size (224, 256, 384 or 512) and input are the same as for the sha3 function. Panics if the size is invalid.
+pub fn keccak<T: Input>(size: usize, input: T) -> String {
+    bytes_to_hex(&keccak_bytes(size, input))
+}
+
+/// The Keccak function returning the raw bytes of the hash instead of a hex string. The size and input are the same as for the keccak function.
+pub fn keccak_bytes<T: Input>(size: usize, input: T) -> Vec<u8> {
+    try_keccak_bytes(size, input).unwrap_or_else(|e| panic!("{}", e))
+}
+
+/// Fallible version of the keccak function, which returns an error instead of panicking when the size is invalid.
+pub fn try_keccak<T: Input>(size: usize, input: T) -> Result<String, Error> {
+    try_keccak_bytes(size, input).map(|hash| bytes_to_hex(&hash))
+}
+
+/// Fallible version of the keccak_bytes function, which returns an error instead of panicking when the size is invalid.
+pub fn try_keccak_bytes<T: Input>(size: usize, input: T) -> Result<Vec<u8>, Error> {
+    check_size(size)?;
+
+    let mut m = input.convert().to_vec();
+    m.push(0x01); // Add the first padding bit, without any domain separator
+    Ok(utils::keccak(size * 2, size, m))
+}
+
+/// Shortcut for the Keccak-224 function, the input can be of any type that implements the Input trait.
+pub fn keccak224<T: Input>(input: T) -> String {
+    keccak(224, input)
+}
+
+/// Shortcut for the Keccak-256 function, the input can be of any type that implements the Input trait.
+pub fn keccak256<T: Input>(input: T) -> String {
+    keccak(256, input)
+}
+
+/// Shortcut for the Keccak-384 function, the input can be of any type that implements the Input trait.
+pub fn keccak384<T: Input>(input: T) -> String {
+    keccak(384, input)
+}
+
+/// Shortcut for the Keccak-512 function, the input can be of any type that implements the Input trait.
+pub fn keccak512<T: Input>(input: T) -> String {
+    keccak(512, input)
+}
+
+/// Shortcut for the Keccak-224 function returning the 28 bytes of the hash.
+pub fn keccak224_bytes<T: Input>(input: T) -> [u8; 28] {
+    // The length always matches, since the output size is given by the Keccak variant.
+    keccak_bytes(224, input).try_into().unwrap()
+}
+
+/// Shortcut for the Keccak-256 function returning the 32 bytes of the hash.
+pub fn keccak256_bytes<T: Input>(input: T) -> [u8; 32] {
+    keccak_bytes(256, input).try_into().unwrap()
+}
+
+/// Shortcut for the Keccak-384 function returning the 48 bytes of the hash.
+pub fn keccak384_bytes<T: Input>(input: T) -> [u8; 48] {
+    keccak_bytes(384, input).try_into().unwrap()
+}
+
+/// Shortcut for the Keccak-512 function returning the 64 bytes of the hash.
+pub fn keccak512_bytes<T: Input>(input: T) -> [u8; 64] {
+    keccak_bytes(512, input).try_into().unwrap()
+}
+
+/// Incremental version of the Keccak function for input which is not available all at once. It works like the Sha3Hasher, but with the original Keccak padding.
+#[derive(Clone)]
+pub struct KeccakHasher {
+    sponge: Sponge,
+    size: usize,
+}
+
+impl KeccakHasher {
+    /// Create a new hasher, the size is the final length of the output in bits (has to be one of the standard sizes 224, 256, 384 or 512). Panics if the size is invalid.
+    pub fn new(size: usize) -> KeccakHasher {
+        KeccakHasher::try_new(size).unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    /// Fallible version of new, which returns an error instead of panicking when the size is invalid.
+    pub fn try_new(size: usize) -> Result<KeccakHasher, Error> {
+        check_size(size)?;
+
+        Ok(KeccakHasher {
+            sponge: Sponge::new(size * 2),
+            size,
+        })
+    }
+
+    /// Add more input to the hasher, the input can be of any type that implements the Input trait.
+    pub fn update<T: Input>(&mut self, input: T) {
+        self.sponge.update(input.convert());
+    }
+
+    /// Add input which is read in chunks to the hasher, like a file, a path or a reader. Returns an error if reading the input fails.
+    pub fn update_stream<S: StreamInput>(&mut self, input: S) -> io::Result<()> {
+        input.write_to(self)
+    }
+
+    /// Consume the hasher and return the hash of all the input given so far.
+    pub fn finalize(self) -> String {
+        bytes_to_hex(&self.finalize_bytes())
+    }
+
+    /// Consume the hasher and return the raw bytes of the hash of all the input given so far.
+    pub fn finalize_bytes(self) -> Vec<u8> {
+        self.sponge.finalize(0x01, self.size)
+    }
+}
+
+impl io::Write for KeccakHasher {
+    /// Add the buffer to the input of the hasher, so a file or socket can be hashed with `io::copy`. All of the buffer is always written.
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.update(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sha3::sha3_256;
+
+    #[test]
+    fn test_keccak224() {
+        assert_eq!(
+            keccak224(""),
+            "f71837502ba8e10837bdd8d365adb85591895602fc552b48b7390abd"
+        );
+    }
+
+    #[test]
+    fn test_keccak256() {
+        // The well known Ethereum test vectors.
+        assert_eq!(
+            keccak256(""),
+            "c5d2460186f7233c927e7db2dcc703c0e500b653ca82273b7bfad8045d85a470"
+        );
+
+        assert_eq!(
+            keccak256("The quick brown fox jumps over the lazy dog"),
+            "4d741b6f1eb29cb2a9b9911c82f56fa8d73b04959d3d9d222895df6c0b28aa15"
+        );
+
+        // The function selector of an ERC-20 transfer is the first 4 bytes of the hash of the signature.
+        assert_eq!(
+            keccak256_bytes("transfer(address,uint256)")[..4],
+            [0xa9, 0x05, 0x9c, 0xbb]
+        );
+
+        assert_ne!(keccak256(""), sha3_256(""));
+    }
+
+    #[test]
+    fn test_keccak384() {
+        assert_eq!(
+            keccak384(""),
+            "2c23146a63a29acf99e73b88f8c24eaa7dc60aa771780ccc006afbfa8fe2479b2dd2b21362337441ac12b515911957ff"
+        );
+    }
+
+    #[test]
+    fn test_keccak512() {
+        assert_eq!(
+            keccak512(""),
+            "0eab42de4c3ceb9235fc91acffe746b29c29a8c366b7c60e4e67c466f36a4304c00fa9caf9d87976ba469bcbe06713b435f091ef2769fb160cdab33d3670680e"
+        );
+    }
+
+    #[test]
+    fn test_keccak_hasher() {
+        let mut hasher = KeccakHasher::new(256);
+        hasher.update("The quick brown fox ");
+        hasher.update("jumps over the lazy dog");
+        assert_eq!(
+            hasher.finalize(),
+            keccak256("The quick brown fox jumps over the lazy dog")
+        );
+
+        assert_eq!(try_keccak(100, ""), Err(Error::InvalidSize(100)));
+        assert!(KeccakHasher::try_new(160).is_err());
+    }
+}
@@ -0,0 +1,352 @@
+use crate::cshake::CShakeHasher;
+use crate::error::Error;
+use crate::input::Input;
+use crate::shake::{check_capacity, check_output_length};
+use crate::sponge::XofReader;
+use crate::utils::{bytepad, bytes_to_hex, constant_time_eq, encode_string, right_encode};
+
+/// The shortest tag in bits which is accepted by verify, since section 8.4.2 of SP 800-185 requires KMAC tags used as a MAC to be at least 32 bits.
+const MIN_TAG_SIZE: usize = 32;
+
+/// The KMAC message authentication code as defined in section 4 of SP 800-185. The capacity has to be 128 or 256, the key and input can be of any type that implements the Input trait, the size is the length of the tag in bits (has to be a number divisible by 8) and the customization string can be used to get a different function for each use. Panics if the capacity or size is invalid.
+pub fn kmac<K: Input, T: Input, S: Input>(
+    capacity: usize,
+    key: K,
+    input: T,
+    size: usize,
+    customization: S,
+) -> String {
+    bytes_to_hex(&kmac_bytes(capacity, key, input, size, customization))
+}
+
+/// The KMAC function returning the raw bytes of the tag instead of a hex string. The parameters are the same as for the kmac function.
+pub fn kmac_bytes<K: Input, T: Input, S: Input>(
+    capacity: usize,
+    key: K,
+    input: T,
+    size: usize,
+    customization: S,
+) -> Vec<u8> {
+    try_kmac_bytes(capacity, key, input, size, customization).unwrap_or_else(|e| panic!("{}", e))
+}
+
+/// Fallible version of the kmac_bytes function, which returns an error instead of panicking when the capacity or size is invalid.
+pub fn try_kmac_bytes<K: Input, T: Input, S: Input>(
+    capacity: usize,
+    key: K,
+    input: T,
+    size: usize,
+    customization: S,
+) -> Result<Vec<u8>, Error> {
+    let mut kmac = Kmac::try_new(capacity, key, customization)?;
+    kmac.update(input);
+    kmac.try_finalize_bytes(size)
+}
+
+/// Shortcut for the KMAC128 function, the parameters are the same as for the kmac function.
+pub fn kmac128<K: Input, T: Input, S: Input>(
+    key: K,
+    input: T,
+    size: usize,
+    customization: S,
+) -> String {
+    kmac(128, key, input, size, customization)
+}
+
+/// Shortcut for the KMAC256 function, the parameters are the same as for the kmac function.
+pub fn kmac256<K: Input, T: Input, S: Input>(
+    key: K,
+    input: T,
+    size: usize,
+    customization: S,
+) -> String {
+    kmac(256, key, input, size, customization)
+}
+
+/// The KMACXOF function as defined in section 4.3.1 of SP 800-185, which is KMAC where the output length is not part of the input, so the output can be read like from SHAKE. The parameters are the same as for the kmac function, but here the size is just the number of bits returned (has to be a number divisible by 8). Panics if the capacity or size is invalid.
+pub fn kmacxof<K: Input, T: Input, S: Input>(
+    capacity: usize,
+    key: K,
+    input: T,
+    size: usize,
+    customization: S,
+) -> String {
+    bytes_to_hex(&kmacxof_bytes(capacity, key, input, size, customization))
+}
+
+/// The KMACXOF function returning the raw bytes of the output instead of a hex string. The parameters are the same as for the kmacxof function.
+pub fn kmacxof_bytes<K: Input, T: Input, S: Input>(
+    capacity: usize,
+    key: K,
+    input: T,
+    size: usize,
+    customization: S,
+) -> Vec<u8> {
+    try_kmacxof_bytes(capacity, key, input, size, customization).unwrap_or_else(|e| panic!("{}", e))
+}
+
+/// Fallible version of the kmacxof_bytes function, which returns an error instead of panicking when the capacity or size is invalid.
+pub fn try_kmacxof_bytes<K: Input, T: Input, S: Input>(
+    capacity: usize,
+    key: K,
+    input: T,
+    size: usize,
+    customization: S,
+) -> Result<Vec<u8>, Error> {
+    let mut kmac = Kmac::try_new(capacity, key, customization)?;
+    check_output_length(size)?;
+    kmac.update(input);
+
+    let mut out = vec![0; size / 8];
+    kmac.finalize_xof().squeeze(&mut out);
+    Ok(out)
+}
+
+/// The KMACXOF128 function, which is KMAC128 where the output length is not part of the input, so the output can be read like from SHAKE. Here, the size is just the number of bits returned (has to be a number divisible by 8).
+pub fn kmacxof128<K: Input, T: Input, S: Input>(
+    key: K,
+    input: T,
+    size: usize,
+    customization: S,
+) -> String {
+    kmacxof(128, key, input, size, customization)
+}
+
+/// The KMACXOF256 function, which is KMAC256 where the output length is not part of the input, so the output can be read like from SHAKE. Here, the size is just the number of bits returned (has to be a number divisible by 8).
+pub fn kmacxof256<K: Input, T: Input, S: Input>(
+    key: K,
+    input: T,
+    size: usize,
+    customization: S,
+) -> String {
+    kmacxof(256, key, input, size, customization)
+}
+
+/// Incremental version of KMAC and KMACXOF for input which is not available all at once. The key and customization string are absorbed when the Kmac is created, so it can be cloned to authenticate several messages with the same key.
+#[derive(Clone)]
+pub struct Kmac {
+    hasher: CShakeHasher,
+}
+
+impl Kmac {
+    /// Create a new KMAC with the given capacity (128 or 256), key and customization string. Panics if the capacity is invalid.
+    pub fn new<K: Input, S: Input>(capacity: usize, key: K, customization: S) -> Kmac {
+        Kmac::try_new(capacity, key, customization).unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    /// Fallible version of new, which returns an error instead of panicking when the capacity is invalid.
+    pub fn try_new<K: Input, S: Input>(
+        capacity: usize,
+        key: K,
+        customization: S,
+    ) -> Result<Kmac, Error> {
+        check_capacity(capacity)?;
+
+        // KMAC is cSHAKE with the function name "KMAC", where the key is padded to a whole number of blocks in front of the input.
+        let mut hasher = CShakeHasher::try_new(capacity, "KMAC", customization)?;
+        let rate = (1600 - capacity * 2) / 8;
+        hasher.update(bytepad(encode_string(key.convert()), rate));
+
+        Ok(Kmac { hasher })
+    }
+
+    /// Add more input to the KMAC, the input can be of any type that implements the Input trait.
+    pub fn update<T: Input>(&mut self, input: T) {
+        self.hasher.update(input);
+    }
+
+    /// Consume the KMAC and return the tag with the given size in bits (has to be a number divisible by 8).
+    pub fn finalize(self, size: usize) -> String {
+        bytes_to_hex(&self.finalize_bytes(size))
+    }
+
+    /// Consume the KMAC and return the raw bytes of the tag with the given size in bits (has to be a number divisible by 8).
+    pub fn finalize_bytes(self, size: usize) -> Vec<u8> {
+        self.try_finalize_bytes(size)
+            .unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    /// Fallible version of finalize_bytes, which returns an error instead of panicking when the size is invalid.
+    pub fn try_finalize_bytes(mut self, size: usize) -> Result<Vec<u8>, Error> {
+        check_output_length(size)?;
+
+        // The length of the output is the last part of the input, so tags of different lengths are unrelated.
+        self.hasher.update(right_encode(size));
+        self.hasher.try_finalize_bytes(size)
+    }
+
+    /// Consume the KMAC and return a reader for the output of KMACXOF, where any amount of output can be read.
+    pub fn finalize_xof(mut self) -> XofReader {
+        // KMACXOF uses an output length of 0, since it is not known in advance.
+        self.hasher.update(right_encode(0));
+        self.hasher.finalize_xof()
+    }
+
+    /// Consume the KMAC and check whether the tag is correct for the input given so far. The tag size is the expected size of the tag in bits (has to be a number divisible by 8), which is the output length of KMAC, and the comparison is done in constant time. A tag which does not have the expected size or is shorter than 32 bits is never correct.
+    pub fn verify(self, tag: &[u8], tag_size: usize) -> bool {
+        // The size is not taken from the tag itself, since a short tag made up by an attacker would then be accepted where a long tag is expected.
+        if tag_size < MIN_TAG_SIZE || tag.len() * 8 != tag_size {
+            return false;
+        }
+
+        constant_time_eq(&self.finalize_bytes(tag_size), tag)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn key() -> Vec<u8> {
+        (0x40..=0x5F).collect()
+    }
+
+    #[test]
+    fn test_kmac128() {
+        // Sample #1, #2 and #3 from the KMAC samples of NIST.
+        assert_eq!(
+            kmac128(key(), [0x00, 0x01, 0x02, 0x03].as_slice(), 256, ""),
+            "e5780b0d3ea6f7d3a429c5706aa43a00fadbd7d49628839e3187243f456ee14e"
+        );
+        assert_eq!(
+            kmac128(
+                key(),
+                [0x00, 0x01, 0x02, 0x03].as_slice(),
+                256,
+                "My Tagged Application"
+            ),
+            "3b1fba963cd8b0b59e8c1a6d71888b7143651af8ba0a7070c0979e2811324aa5"
+        );
+
+        let input: Vec<u8> = (0x00..=0xC7).collect();
+        assert_eq!(
+            kmac128(key(), input, 256, "My Tagged Application"),
+            "1f5b4e6cca02209e0dcb5ca635b89a15e271ecc760071dfd805faa38f9729230"
+        );
+    }
+
+    #[test]
+    fn test_kmac256() {
+        // Sample #4 from the KMAC samples of NIST.
+        assert_eq!(
+            kmac256(
+                key(),
+                [0x00, 0x01, 0x02, 0x03].as_slice(),
+                512,
+                "My Tagged Application"
+            ),
+            "20c570c31346f703c9ac36c61c03cb64c3970d0cfc787e9b79599d273a68d2f7f69d4cc3de9d104a351689f27cf6f5951f0103f33f4f24871024d9c27773a8dd"
+        );
+    }
+
+    #[test]
+    fn test_kmacxof() {
+        // Sample #1 from the KMACXOF samples of NIST.
+        assert_eq!(
+            kmacxof128(key(), [0x00, 0x01, 0x02, 0x03].as_slice(), 256, ""),
+            "cd83740bbd92ccc8cf032b1481a0f4460e7ca9dd12b08a0c4031178bacd6ec35"
+        );
+        // Sample #4 from the KMACXOF samples of NIST.
+        assert_eq!(
+            kmacxof256(
+                key(),
+                [0x00, 0x01, 0x02, 0x03].as_slice(),
+                512,
+                "My Tagged Application"
+            ),
+            "1755133f1534752aad0748f2c706fb5c784512cab835cd15676b16c0c6647fa96faa7af634a0bf8ff6df39374fa00fad9a39e322a7c92065a64eb1fb0801eb2b"
+        );
+    }
+
+    #[test]
+    fn test_kmac_streaming() {
+        let mut kmac = Kmac::new(128, key(), "App");
+        kmac.update("The quick brown fox ");
+        kmac.update("jumps over the lazy dog");
+
+        assert_eq!(
+            kmac.clone().finalize(384),
+            kmac128(
+                key(),
+                "The quick brown fox jumps over the lazy dog",
+                384,
+                "App"
+            )
+        );
+
+        // The output of KMACXOF is not a prefix of KMAC, since the output length is part of the input.
+        let mut out = [0; 48];
+        kmac.finalize_xof().squeeze(&mut out);
+        assert_ne!(
+            bytes_to_hex(&out),
+            kmac128(
+                key(),
+                "The quick brown fox jumps over the lazy dog",
+                384,
+                "App"
+            )
+        );
+    }
+
+    #[test]
+    fn test_kmac_verify() {
+        let tag = kmac_bytes(256, key(), "message", 256, "");
+
+        let mut kmac = Kmac::new(256, key(), "");
+        kmac.update("message");
+        assert!(kmac.clone().verify(&tag, 256));
+
+        let mut modified = tag.clone();
+        modified[31] ^= 0x01;
+        assert!(!kmac.clone().verify(&modified, 256));
+        assert!(!kmac.clone().verify(&tag[..16], 256));
+
+        // A correct tag of another size is rejected when a longer tag is expected.
+        let minimum = kmac_bytes(256, key(), "message", 32, "");
+        assert!(kmac.clone().verify(&minimum, 32));
+        assert!(!kmac.clone().verify(&minimum, 256));
+        assert!(!kmac.clone().verify(&tag, 512));
+
+        // Empty tags and tags shorter than 32 bits are rejected, even when they are correct for their size.
+        let short = kmac_bytes(256, key(), "message", 24, "");
+        assert!(!kmac.clone().verify(&[], 0));
+        assert!(!kmac.clone().verify(&short, 24));
+        assert!(!kmac.verify(&minimum, 36));
+    }
+
+    #[test]
+    fn test_try_kmac() {
+        assert_eq!(
+            try_kmac_bytes(64, key(), "", 256, ""),
+            Err(Error::InvalidCapacity(64))
+        );
+        assert_eq!(
+            try_kmac_bytes(128, key(), "", 255, ""),
+            Err(Error::InvalidOutputLength(255))
+        );
+    }
+
+    #[test]
+    fn test_try_kmacxof() {
+        // Sample #4 from the KMACXOF samples of NIST.
+        assert_eq!(
+            try_kmacxof_bytes(
+                256,
+                key(),
+                [0x00, 0x01, 0x02, 0x03].as_slice(),
+                512,
+                "My Tagged Application"
+            )
+            .map(|out| bytes_to_hex(&out)),
+            Ok("1755133f1534752aad0748f2c706fb5c784512cab835cd15676b16c0c6647fa96faa7af634a0bf8ff6df39374fa00fad9a39e322a7c92065a64eb1fb0801eb2b".to_string())
+        );
+        assert_eq!(
+            try_kmacxof_bytes(64, key(), "", 256, ""),
+            Err(Error::InvalidCapacity(64))
+        );
+        assert_eq!(
+            try_kmacxof_bytes(256, key(), "", 255, ""),
+            Err(Error::InvalidOutputLength(255))
+        );
+    }
+}
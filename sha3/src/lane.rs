@@ -0,0 +1,194 @@
+use std::fmt;
+use std::ops::{BitAnd, BitXor, BitXorAssign, Not};
+
+/// The trait implemented by the lanes of a Keccak state. A lane holds the w bits of the state with the same x and y coordinates, where the width of the permutation is b = 25w as described in section 3.1 of FIPS 202. It is implemented for u64, u32, u16 and u8 (Keccak-f\[1600\], \[800\], \[400\] and \[200\]) and for SmallLane with 1, 2 or 4 bits (Keccak-f\[25\], \[50\] and \[100\]).
+pub trait Lane:
+    Copy
+    + Default
+    + PartialEq
+    + fmt::Debug
+    + BitXor<Output = Self>
+    + BitXorAssign
+    + BitAnd<Output = Self>
+    + Not<Output = Self>
+{
+    /// The number of bits w in a lane, which has to be a power of 2 from 1 to 64.
+    const BITS: u32;
+
+    /// Rotate the bits of the lane to the left by n places, so bit z moves to bit z + n mod w.
+    fn rotate_left(self, n: u32) -> Self;
+
+    /// Truncate a 64 bit word to the w least significant bits, which is how the round constants of Keccak-f\[1600\] are used for smaller lanes.
+    fn from_u64(word: u64) -> Self;
+
+    /// The bits of the lane as a 64 bit word.
+    fn to_u64(self) -> u64;
+}
+
+impl Lane for u64 {
+    const BITS: u32 = u64::BITS;
+
+    fn rotate_left(self, n: u32) -> u64 {
+        u64::rotate_left(self, n)
+    }
+
+    fn from_u64(word: u64) -> u64 {
+        word
+    }
+
+    fn to_u64(self) -> u64 {
+        self
+    }
+}
+
+impl Lane for u32 {
+    const BITS: u32 = u32::BITS;
+
+    fn rotate_left(self, n: u32) -> u32 {
+        u32::rotate_left(self, n)
+    }
+
+    fn from_u64(word: u64) -> u32 {
+        word as u32
+    }
+
+    fn to_u64(self) -> u64 {
+        self as u64
+    }
+}
+
+impl Lane for u16 {
+    const BITS: u32 = u16::BITS;
+
+    fn rotate_left(self, n: u32) -> u16 {
+        u16::rotate_left(self, n)
+    }
+
+    fn from_u64(word: u64) -> u16 {
+        word as u16
+    }
+
+    fn to_u64(self) -> u64 {
+        self as u64
+    }
+}
+
+impl Lane for u8 {
+    const BITS: u32 = u8::BITS;
+
+    fn rotate_left(self, n: u32) -> u8 {
+        u8::rotate_left(self, n)
+    }
+
+    fn from_u64(word: u64) -> u8 {
+        word as u8
+    }
+
+    fn to_u64(self) -> u64 {
+        self as u64
+    }
+}
+
+/// A lane of W bits (1, 2 or 4) for the smallest widths Keccak-f\[25\], \[50\] and \[100\], since Rust has no integer types that small. The bits are stored in the least significant bits of a byte and the other bits are always 0.
+#[derive(Clone, Copy, Default, PartialEq, Eq, Debug)]
+pub struct SmallLane<const W: u32>(u8);
+
+impl<const W: u32> SmallLane<W> {
+    /// Mask with the W least significant bits set.
+    const MASK: u8 = ((1u16 << W) - 1) as u8;
+
+    /// Create a lane from the W least significant bits of the value, the other bits are ignored.
+    pub fn new(value: u8) -> SmallLane<W> {
+        SmallLane(value & Self::MASK)
+    }
+
+    /// The bits of the lane.
+    pub fn value(self) -> u8 {
+        self.0
+    }
+}
+
+impl<const W: u32> BitXor for SmallLane<W> {
+    type Output = SmallLane<W>;
+
+    fn bitxor(self, other: SmallLane<W>) -> SmallLane<W> {
+        SmallLane(self.0 ^ other.0)
+    }
+}
+
+impl<const W: u32> BitXorAssign for SmallLane<W> {
+    fn bitxor_assign(&mut self, other: SmallLane<W>) {
+        self.0 ^= other.0;
+    }
+}
+
+impl<const W: u32> BitAnd for SmallLane<W> {
+    type Output = SmallLane<W>;
+
+    fn bitand(self, other: SmallLane<W>) -> SmallLane<W> {
+        SmallLane(self.0 & other.0)
+    }
+}
+
+impl<const W: u32> Not for SmallLane<W> {
+    type Output = SmallLane<W>;
+
+    fn not(self) -> SmallLane<W> {
+        // Only flip the W bits of the lane, so the unused bits stay 0.
+        SmallLane(!self.0 & Self::MASK)
+    }
+}
+
+impl<const W: u32> Lane for SmallLane<W> {
+    const BITS: u32 = W;
+
+    fn rotate_left(self, n: u32) -> SmallLane<W> {
+        let n = n % W;
+        if n == 0 {
+            return self;
+        }
+
+        SmallLane(((self.0 << n) | (self.0 >> (W - n))) & Self::MASK)
+    }
+
+    fn from_u64(word: u64) -> SmallLane<W> {
+        SmallLane::new(word as u8)
+    }
+
+    fn to_u64(self) -> u64 {
+        self.0 as u64
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_small_lane() {
+        let lane = SmallLane::<4>::new(0xF6);
+        assert_eq!(lane.value(), 0x6);
+        assert_eq!((!lane).value(), 0x9);
+        assert_eq!(lane.rotate_left(1).value(), 0xC);
+        assert_eq!(lane.rotate_left(3).value(), 0x3);
+        assert_eq!(lane.rotate_left(4), lane);
+
+        let bit = SmallLane::<1>::from_u64(0x8003);
+        assert_eq!(bit.value(), 1);
+        assert_eq!((!bit).value(), 0);
+        assert_eq!(bit.rotate_left(7), bit);
+
+        assert_eq!(
+            (SmallLane::<2>::new(0b01) ^ SmallLane::new(0b11)).value(),
+            0b10
+        );
+    }
+
+    #[test]
+    fn test_truncate_round_constant() {
+        // The round constants are truncated to the size of the lane.
+        assert_eq!(<u32 as Lane>::from_u64(0x800000008000808A), 0x8000808A);
+        assert_eq!(<u8 as Lane>::from_u64(0x800000008000808A), 0x8A);
+        assert_eq!(SmallLane::<2>::from_u64(0x800000008000808A).to_u64(), 0b10);
+    }
+}
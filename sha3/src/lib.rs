@@ -1,9 +1,96 @@
+mod builder;
+mod buildhasher;
+mod cshake;
+mod drbg;
+mod duplex;
+mod error;
+mod hashvalue;
+mod hkdf;
+mod hmac;
 mod input;
+mod kangarootwelve;
+mod keccak;
+mod kmac;
+mod lane;
+mod parallelhash;
+mod pbkdf2;
+#[cfg(feature = "rand_core")]
+mod rng;
+#[cfg(feature = "digest")]
+mod rustcrypto;
 mod sha3;
 mod shake;
+mod sponge;
+mod spongewrap;
 mod state;
+mod tuplehash;
+mod turboshake;
 mod utils;
 
-pub use crate::input::Input;
-pub use crate::sha3::{sha3, sha3_224, sha3_256, sha3_384, sha3_512};
-pub use crate::shake::{shake, shake128, shake256};
+pub use crate::builder::{KeccakBuilder, KeccakSponge};
+pub use crate::buildhasher::{Sha3BuildHasher, Sha3MapHasher};
+pub use crate::cshake::{
+    cshake, cshake128, cshake256, cshake_bytes, try_cshake, try_cshake_bytes, CShakeHasher,
+};
+pub use crate::drbg::HashDrbg;
+pub use crate::duplex::Duplex;
+pub use crate::error::Error;
+pub use crate::hashvalue::{
+    Algorithm, HashValue, Keccak224Algorithm, Keccak224Digest, Keccak256Algorithm, Keccak256Digest,
+    Keccak384Algorithm, Keccak384Digest, Keccak512Algorithm, Keccak512Digest, Sha3_224Algorithm,
+    Sha3_224Digest, Sha3_256Algorithm, Sha3_256Digest, Sha3_384Algorithm, Sha3_384Digest,
+    Sha3_512Algorithm, Sha3_512Digest, Shake128Algorithm, Shake128Digest, Shake256Algorithm,
+    Shake256Digest,
+};
+pub use crate::hkdf::{hkdf, hkdf_expand, hkdf_extract};
+pub use crate::hmac::{
+    hmac, hmac_bytes, hmac_sha3_224, hmac_sha3_256, hmac_sha3_384, hmac_sha3_512, try_hmac_bytes,
+    Hmac,
+};
+pub use crate::input::{FromChunks, FromReader, Input, StreamInput};
+pub use crate::kangarootwelve::{
+    kangarootwelve, kangarootwelve_bytes, kt128, kt256, try_kangarootwelve_bytes, KangarooTwelve,
+};
+pub use crate::keccak::{
+    keccak, keccak224, keccak224_bytes, keccak256, keccak256_bytes, keccak384, keccak384_bytes,
+    keccak512, keccak512_bytes, keccak_bytes, try_keccak, try_keccak_bytes, KeccakHasher,
+};
+pub use crate::kmac::{
+    kmac, kmac128, kmac256, kmac_bytes, kmacxof, kmacxof128, kmacxof256, kmacxof_bytes,
+    try_kmac_bytes, try_kmacxof_bytes, Kmac,
+};
+pub use crate::lane::{Lane, SmallLane};
+pub use crate::parallelhash::{
+    parallelhash, parallelhash128, parallelhash256, parallelhash_bytes, parallelhashxof,
+    parallelhashxof128, parallelhashxof256, parallelhashxof_bytes, try_parallelhash_bytes,
+    try_parallelhashxof_bytes, ParallelHasher,
+};
+pub use crate::pbkdf2::pbkdf2;
+#[cfg(feature = "rand_core")]
+pub use crate::rng::ShakeRng;
+#[cfg(feature = "digest")]
+pub use crate::rustcrypto::{
+    Keccak224, Keccak256, Keccak384, Keccak512, Sha3_224, Sha3_256, Sha3_384, Sha3_512, Shake128,
+    Shake256,
+};
+pub use crate::sha3::{
+    hash_reader, hash_reader_bytes, sha3, sha3_224, sha3_224_bytes, sha3_256, sha3_256_bytes,
+    sha3_384, sha3_384_bytes, sha3_512, sha3_512_bytes, sha3_bits, sha3_bytes, try_sha3,
+    try_sha3_bits, try_sha3_bytes, Sha3Hasher,
+};
+pub use crate::shake::{
+    shake, shake128, shake128_bytes, shake256, shake256_bytes, shake_bits, shake_bytes, try_shake,
+    try_shake_bits, try_shake_bytes, ShakeHasher,
+};
+pub use crate::sponge::XofReader;
+pub use crate::spongewrap::SpongeWrap;
+pub use crate::state::State;
+pub use crate::tuplehash::{
+    try_tuplehash_bytes, try_tuplehashxof_bytes, tuplehash, tuplehash128, tuplehash256,
+    tuplehash_bytes, tuplehashxof, tuplehashxof128, tuplehashxof256, tuplehashxof_bytes,
+};
+pub use crate::turboshake::{
+    try_turboshake_bytes, turboshake, turboshake128, turboshake256, turboshake_bytes,
+    TurboShakeHasher,
+};
+pub use crate::utils::{bytes_to_hex, hex_to_bytes};
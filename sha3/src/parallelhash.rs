@@ -0,0 +1,376 @@
+use crate::cshake::CShakeHasher;
+use crate::error::Error;
+use crate::input::Input;
+use crate::shake::{check_capacity, check_output_length};
+use crate::sponge::{Sponge, XofReader};
+use crate::utils::{bytes_to_hex, left_encode, right_encode};
+
+/// The ParallelHash function as defined in section 6 of SP 800-185. The input is split into blocks of block_size bytes which are hashed independently of each other, and the hashes of the blocks are then hashed together. The capacity has to be 128 or 256, the size is the final length of the output in bits (has to be a number divisible by 8) and the customization string can be used to get a different function for each use. Panics if the capacity, block size or size is invalid.
+pub fn parallelhash<T: Input, S: Input>(
+    capacity: usize,
+    input: T,
+    block_size: usize,
+    size: usize,
+    customization: S,
+) -> String {
+    bytes_to_hex(&parallelhash_bytes(
+        capacity,
+        input,
+        block_size,
+        size,
+        customization,
+    ))
+}
+
+/// The ParallelHash function returning the raw bytes of the output instead of a hex string. The parameters are the same as for the parallelhash function.
+pub fn parallelhash_bytes<T: Input, S: Input>(
+    capacity: usize,
+    input: T,
+    block_size: usize,
+    size: usize,
+    customization: S,
+) -> Vec<u8> {
+    try_parallelhash_bytes(capacity, input, block_size, size, customization)
+        .unwrap_or_else(|e| panic!("{}", e))
+}
+
+/// Fallible version of the parallelhash_bytes function, which returns an error instead of panicking when the capacity, block size or size is invalid.
+pub fn try_parallelhash_bytes<T: Input, S: Input>(
+    capacity: usize,
+    input: T,
+    block_size: usize,
+    size: usize,
+    customization: S,
+) -> Result<Vec<u8>, Error> {
+    let mut hasher = ParallelHasher::try_new(capacity, block_size, customization)?;
+    hasher.update(input);
+    hasher.try_finalize_bytes(size)
+}
+
+/// Shortcut for the ParallelHash128 function, the parameters are the same as for the parallelhash function.
+pub fn parallelhash128<T: Input, S: Input>(
+    input: T,
+    block_size: usize,
+    size: usize,
+    customization: S,
+) -> String {
+    parallelhash(128, input, block_size, size, customization)
+}
+
+/// Shortcut for the ParallelHash256 function, the parameters are the same as for the parallelhash function.
+pub fn parallelhash256<T: Input, S: Input>(
+    input: T,
+    block_size: usize,
+    size: usize,
+    customization: S,
+) -> String {
+    parallelhash(256, input, block_size, size, customization)
+}
+
+/// The ParallelHashXOF function, which is ParallelHash where the output length is not part of the input. The parameters are the same as for the parallelhash function, but here the size is just the number of bits returned (has to be a number divisible by 8). Panics if the capacity, block size or size is invalid.
+pub fn parallelhashxof<T: Input, S: Input>(
+    capacity: usize,
+    input: T,
+    block_size: usize,
+    size: usize,
+    customization: S,
+) -> String {
+    bytes_to_hex(&parallelhashxof_bytes(
+        capacity,
+        input,
+        block_size,
+        size,
+        customization,
+    ))
+}
+
+/// The ParallelHashXOF function returning the raw bytes of the output instead of a hex string. The parameters are the same as for the parallelhashxof function.
+pub fn parallelhashxof_bytes<T: Input, S: Input>(
+    capacity: usize,
+    input: T,
+    block_size: usize,
+    size: usize,
+    customization: S,
+) -> Vec<u8> {
+    try_parallelhashxof_bytes(capacity, input, block_size, size, customization)
+        .unwrap_or_else(|e| panic!("{}", e))
+}
+
+/// Fallible version of the parallelhashxof_bytes function, which returns an error instead of panicking when the capacity, block size or size is invalid.
+pub fn try_parallelhashxof_bytes<T: Input, S: Input>(
+    capacity: usize,
+    input: T,
+    block_size: usize,
+    size: usize,
+    customization: S,
+) -> Result<Vec<u8>, Error> {
+    let mut hasher = ParallelHasher::try_new(capacity, block_size, customization)?;
+    check_output_length(size)?;
+    hasher.update(input);
+
+    let mut out = vec![0; size / 8];
+    hasher.finalize_xof().squeeze(&mut out);
+    Ok(out)
+}
+
+/// The ParallelHashXOF128 function, which is ParallelHash128 where the output length is not part of the input. Here, the size is just the number of bits returned (has to be a number divisible by 8).
+pub fn parallelhashxof128<T: Input, S: Input>(
+    input: T,
+    block_size: usize,
+    size: usize,
+    customization: S,
+) -> String {
+    parallelhashxof(128, input, block_size, size, customization)
+}
+
+/// The ParallelHashXOF256 function, which is ParallelHash256 where the output length is not part of the input. Here, the size is just the number of bits returned (has to be a number divisible by 8).
+pub fn parallelhashxof256<T: Input, S: Input>(
+    input: T,
+    block_size: usize,
+    size: usize,
+    customization: S,
+) -> String {
+    parallelhashxof(256, input, block_size, size, customization)
+}
+
+/// Incremental version of ParallelHash and ParallelHashXOF for input which is not available all at once. Only the block which is not complete yet is kept in memory, and every block is hashed as soon as it is complete, so the input does not have to fit in memory.
+#[derive(Clone)]
+pub struct ParallelHasher {
+    // cSHAKE with the function name "ParallelHash", which absorbs left_encode(B) and the hash of every block.
+    hasher: CShakeHasher,
+    capacity: usize,
+    block_size: usize,
+    // Input which has not yet filled up a complete block, and the number of blocks which have been hashed.
+    buffer: Vec<u8>,
+    blocks: usize,
+}
+
+impl ParallelHasher {
+    /// Create a new hasher with the given capacity (128 or 256), block size in bytes and customization string. Panics if the capacity or block size is invalid.
+    pub fn new<S: Input>(capacity: usize, block_size: usize, customization: S) -> ParallelHasher {
+        ParallelHasher::try_new(capacity, block_size, customization)
+            .unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    /// Fallible version of new, which returns an error instead of panicking when the capacity or block size is invalid.
+    pub fn try_new<S: Input>(
+        capacity: usize,
+        block_size: usize,
+        customization: S,
+    ) -> Result<ParallelHasher, Error> {
+        check_capacity(capacity)?;
+
+        if block_size == 0 {
+            return Err(Error::InvalidBlockSize(block_size));
+        }
+
+        let mut hasher = CShakeHasher::try_new(capacity, "ParallelHash", customization)?;
+        hasher.update(left_encode(block_size));
+
+        Ok(ParallelHasher {
+            hasher,
+            capacity,
+            block_size,
+            buffer: Vec::with_capacity(block_size),
+            blocks: 0,
+        })
+    }
+
+    /// Add more input to the hasher, the input can be of any type that implements the Input trait. Complete blocks are hashed right away, the rest is kept until more input arrives.
+    pub fn update<T: Input>(&mut self, input: T) {
+        let mut data = input.convert();
+
+        // Fill up the partial block from an earlier update first.
+        if !self.buffer.is_empty() {
+            let needed = (self.block_size - self.buffer.len()).min(data.len());
+            self.buffer.extend_from_slice(&data[..needed]);
+            data = &data[needed..];
+
+            if self.buffer.len() == self.block_size {
+                self.hasher.update(hash_block(self.capacity, &self.buffer));
+                self.blocks += 1;
+                self.buffer.clear();
+            }
+        }
+
+        // Hash whole blocks directly from the input without copying them into the buffer.
+        while data.len() >= self.block_size {
+            self.hasher
+                .update(hash_block(self.capacity, &data[..self.block_size]));
+            self.blocks += 1;
+            data = &data[self.block_size..];
+        }
+
+        self.buffer.extend_from_slice(data);
+    }
+
+    /// Consume the hasher and return the output with the given size in bits (has to be a number divisible by 8).
+    pub fn finalize(self, size: usize) -> String {
+        bytes_to_hex(&self.finalize_bytes(size))
+    }
+
+    /// Consume the hasher and return the raw bytes of the output with the given size in bits (has to be a number divisible by 8).
+    pub fn finalize_bytes(self, size: usize) -> Vec<u8> {
+        self.try_finalize_bytes(size)
+            .unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    /// Fallible version of finalize_bytes, which returns an error instead of panicking when the size is invalid.
+    pub fn try_finalize_bytes(self, size: usize) -> Result<Vec<u8>, Error> {
+        check_output_length(size)?;
+
+        let mut hasher = self.absorb_last_block();
+        hasher.update(right_encode(size));
+        hasher.try_finalize_bytes(size)
+    }
+
+    /// Consume the hasher and return a reader for the output of ParallelHashXOF, where any amount of output can be read.
+    pub fn finalize_xof(self) -> XofReader {
+        // The XOF variants use an output length of 0, since it is not known in advance.
+        let mut hasher = self.absorb_last_block();
+        hasher.update(right_encode(0));
+        hasher.finalize_xof()
+    }
+
+    /// Hash the last block, which can be shorter than the block size, and absorb right_encode(n) with the number of blocks. An empty input has no blocks at all.
+    fn absorb_last_block(mut self) -> CShakeHasher {
+        if !self.buffer.is_empty() {
+            self.hasher.update(hash_block(self.capacity, &self.buffer));
+            self.blocks += 1;
+        }
+
+        self.hasher.update(right_encode(self.blocks));
+        self.hasher
+    }
+}
+
+/// Hash a single block with cSHAKE without a function name or customization string (so SHAKE) and an output of twice the capacity. The blocks do not depend on each other, so they could be hashed in any order or at the same time.
+fn hash_block(capacity: usize, block: &[u8]) -> Vec<u8> {
+    let mut sponge = Sponge::new(capacity * 2);
+    sponge.update(block);
+    sponge.finalize(0x1F, capacity * 2)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const X: [u8; 24] = [
+        0x00, 0x01, 0x02, 0x03, 0x04, 0x05, 0x06, 0x07, 0x10, 0x11, 0x12, 0x13, 0x14, 0x15, 0x16,
+        0x17, 0x20, 0x21, 0x22, 0x23, 0x24, 0x25, 0x26, 0x27,
+    ];
+
+    #[test]
+    fn test_parallelhash128() {
+        // Sample #1 and #2 from the ParallelHash samples of NIST.
+        assert_eq!(
+            parallelhash128(X.as_slice(), 8, 256, ""),
+            "ba8dc1d1d979331d3f813603c67f72609ab5e44b94a0b8f9af46514454a2b4f5"
+        );
+        assert_eq!(
+            parallelhash128(X.as_slice(), 8, 256, "Parallel Data"),
+            "fc484dcb3f84dceedc353438151bee58157d6efed0445a81f165e495795b7206"
+        );
+    }
+
+    #[test]
+    fn test_parallelhash256() {
+        // Sample #4 from the ParallelHash samples of NIST.
+        assert_eq!(
+            parallelhash256(X.as_slice(), 8, 512, ""),
+            "bc1ef124da34495e948ead207dd9842235da432d2bbc54b4c110e64c451105531b7f2a3e0ce055c02805e7c2de1fb746af97a1dd01f43b824e31b87612410429"
+        );
+    }
+
+    #[test]
+    fn test_parallelhashxof() {
+        // Sample #1 from the ParallelHashXOF samples of NIST.
+        assert_eq!(
+            parallelhashxof128(X.as_slice(), 8, 256, ""),
+            "fe47d661e49ffe5b7d999922c062356750caf552985b8e8ce6667f2727c3c8d3"
+        );
+        assert_ne!(
+            parallelhashxof256(X.as_slice(), 8, 512, ""),
+            parallelhash256(X.as_slice(), 8, 512, "")
+        );
+    }
+
+    #[test]
+    fn test_parallelhash_block_size() {
+        // The block size is part of the input, so the same data with another block size gives a different output.
+        assert_ne!(
+            parallelhash128(X.as_slice(), 8, 256, ""),
+            parallelhash128(X.as_slice(), 12, 256, "")
+        );
+        // A last block which is shorter than the block size, and an empty input without any blocks.
+        assert_eq!(parallelhash128(X.as_slice(), 10, 256, "").len(), 64);
+        assert_eq!(parallelhash128("", 10, 256, "").len(), 64);
+    }
+
+    #[test]
+    fn test_parallel_hasher() {
+        let input: Vec<u8> = (0..=255).cycle().take(100).collect();
+
+        // Split the input in every possible place with block sizes that do and do not divide the length of the input.
+        for block_size in [1, 7, 10, 100, 101] {
+            let expected = parallelhash_bytes(256, input.as_slice(), block_size, 512, "Custom");
+
+            for split in 0..=input.len() {
+                let mut hasher = ParallelHasher::new(256, block_size, "Custom");
+                hasher.update(&input[..split]);
+                hasher.update(&input[split..]);
+                assert_eq!(hasher.finalize_bytes(512), expected);
+            }
+        }
+
+        // Sample #1 from the ParallelHashXOF samples of NIST, with the input given one byte at a time.
+        let mut hasher = ParallelHasher::new(128, 8, "");
+        for byte in X {
+            hasher.update([byte].as_slice());
+        }
+        let mut out = [0; 32];
+        hasher.finalize_xof().squeeze(&mut out);
+        assert_eq!(
+            bytes_to_hex(&out),
+            "fe47d661e49ffe5b7d999922c062356750caf552985b8e8ce6667f2727c3c8d3"
+        );
+    }
+
+    #[test]
+    fn test_try_parallelhash() {
+        assert_eq!(
+            try_parallelhash_bytes(128, X.as_slice(), 0, 256, ""),
+            Err(Error::InvalidBlockSize(0))
+        );
+        assert_eq!(
+            try_parallelhash_bytes(512, X.as_slice(), 8, 256, ""),
+            Err(Error::InvalidCapacity(512))
+        );
+        assert_eq!(
+            ParallelHasher::new(128, 8, "").try_finalize_bytes(12),
+            Err(Error::InvalidOutputLength(12))
+        );
+    }
+
+    #[test]
+    fn test_try_parallelhashxof() {
+        // Sample #4 from the ParallelHashXOF samples of NIST.
+        assert_eq!(
+            try_parallelhashxof_bytes(256, X.as_slice(), 8, 512, "")
+                .map(|out| bytes_to_hex(&out)),
+            Ok("c10a052722614684144d28474850b410757e3cba87651ba167a5cbddff7f466675fbf84bcae7378ac444be681d729499afca667fb879348bfdda427863c82f1c".to_string())
+        );
+        assert_eq!(
+            try_parallelhashxof_bytes(128, X.as_slice(), 0, 256, ""),
+            Err(Error::InvalidBlockSize(0))
+        );
+        assert_eq!(
+            try_parallelhashxof_bytes(512, X.as_slice(), 8, 256, ""),
+            Err(Error::InvalidCapacity(512))
+        );
+        assert_eq!(
+            try_parallelhashxof_bytes(128, X.as_slice(), 8, 12, ""),
+            Err(Error::InvalidOutputLength(12))
+        );
+    }
+}
@@ -0,0 +1,138 @@
+use crate::error::Error;
+use crate::hmac::Hmac;
+use crate::input::Input;
+use crate::sha3::check_size;
+
+/// The PBKDF2 password-based key derivation function as defined in section 5.2 of RFC 8018 with HMAC-SHA3 as the pseudorandom function. The size is the size of the SHA-3 hash (224, 256, 384 or 512), the password and salt can be of any type that implements the Input trait, the iterations are the number of HMAC calls for each block of output and the length is the number of bytes of the derived key. Returns an error if the size is invalid, the number of iterations is 0 or the length is more than (2^32 - 1) times the size of the hash in bytes.
+pub fn pbkdf2<P: Input, S: Input>(
+    size: usize,
+    password: P,
+    salt: S,
+    iterations: u32,
+    length: usize,
+) -> Result<Vec<u8>, Error> {
+    check_size(size)?;
+
+    if iterations == 0 {
+        return Err(Error::InvalidIterations(iterations));
+    }
+
+    // Step 1 of section 5.2 of RFC 8018: the block index is a 32 bit integer, so there can be at most 2^32 - 1 blocks.
+    let block_size = size / 8;
+    if length as u64 > u32::MAX as u64 * block_size as u64 {
+        return Err(Error::InvalidKeyLength(length));
+    }
+
+    // The password is the key of every HMAC call, so the inner and outer states with the key absorbed are computed only once and cloned for each call.
+    let prf = Hmac::try_new(size, password)?;
+    let salt = salt.convert();
+    let mut key = Vec::with_capacity(length);
+
+    for i in 1..=length.div_ceil(block_size) {
+        // U_1 = PRF(P, S || INT(i)), where INT(i) is the block index as a big endian 32 bit integer.
+        let mut hmac = prf.clone();
+        hmac.update(salt);
+        hmac.update((i as u32).to_be_bytes().as_slice());
+        let mut u = hmac.finalize_bytes();
+        let mut block = u.clone();
+
+        // U_j = PRF(P, U_{j-1}), and the block is the XOR of all of them.
+        for _ in 1..iterations {
+            let mut hmac = prf.clone();
+            hmac.update(u);
+            u = hmac.finalize_bytes();
+
+            for (b, x) in block.iter_mut().zip(&u) {
+                *b ^= x;
+            }
+        }
+
+        key.extend_from_slice(&block);
+    }
+
+    key.truncate(length);
+    Ok(key)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::bytes_to_hex;
+
+    fn derive(size: usize, password: &str, salt: &str, iterations: u32, length: usize) -> String {
+        bytes_to_hex(&pbkdf2(size, password, salt, iterations, length).unwrap())
+    }
+
+    #[test]
+    fn test_pbkdf2_sha3_256() {
+        // The inputs of the test vectors of RFC 6070, which only has vectors for SHA-1. The expected outputs were computed with the hashlib module of Python.
+        assert_eq!(
+            derive(256, "password", "salt", 1, 20),
+            "94613f3ee2ea730e0b06754f3fc816d4f87c9be9"
+        );
+        assert_eq!(
+            derive(256, "password", "salt", 2, 20),
+            "4c915baedd1773383e77fcfe38114ca7514010ad"
+        );
+        assert_eq!(
+            derive(256, "password", "salt", 4096, 20),
+            "778b6e237a0f49621549ff70d218d2080756b9fb"
+        );
+        // A derived key longer than the hash, which takes 2 blocks.
+        assert_eq!(
+            derive(
+                256,
+                "passwordPASSWORDpassword",
+                "saltSALTsaltSALTsaltSALTsaltSALTsalt",
+                4096,
+                40
+            ),
+            "7aef8f1ad8c7f12205334f624d4af9e2863121618f7a0b3209bef3934801c39feac24ef0ac6a5c25"
+        );
+    }
+
+    #[test]
+    fn test_pbkdf2_sizes() {
+        assert_eq!(
+            derive(224, "password", "salt", 2, 20),
+            "7979d7e05025f5b056e995939694ad55c644f43f"
+        );
+        assert_eq!(
+            derive(384, "password", "salt", 2, 20),
+            "0a163df94a9e97f05fb5bf609c4b02854299dc46"
+        );
+        assert_eq!(
+            derive(512, "password", "salt", 2, 20),
+            "d6824ab17801706ad465f3196eb80dde20378696"
+        );
+        assert_eq!(
+            bytes_to_hex(
+                &pbkdf2(
+                    512,
+                    b"pass\0word".as_slice(),
+                    b"sa\0lt".as_slice(),
+                    4096,
+                    16
+                )
+                .unwrap()
+            ),
+            "c0da8018507821037c76801cccf3cc8a"
+        );
+    }
+
+    #[test]
+    fn test_pbkdf2_errors() {
+        assert_eq!(
+            pbkdf2(256, "password", "salt", 0, 32),
+            Err(Error::InvalidIterations(0))
+        );
+        assert_eq!(
+            pbkdf2(100, "password", "salt", 1, 32),
+            Err(Error::InvalidSize(100))
+        );
+        assert_eq!(
+            pbkdf2(224, "password", "salt", 1, usize::MAX),
+            Err(Error::InvalidKeyLength(usize::MAX))
+        );
+    }
+}
@@ -0,0 +1,148 @@
+use crate::cshake::CShakeHasher;
+use crate::input::Input;
+use crate::shake::ShakeHasher;
+use crate::sponge::XofReader;
+use rand_core::{impls, Error, RngCore, SeedableRng};
+
+/// The number of bytes of output which are absorbed into a new state when the generator is reseeded or forked, which is twice the security level of SHAKE256.
+const CHAIN_SIZE: usize = 64;
+
+/// A deterministic random number generator which reads its output from SHAKE256 of the seed, so the same seed always gives the same stream of numbers. The output is squeezed from a single sponge as it is needed, instead of hashing the seed again for every number. This is meant for reproducible randomness like in simulations and tests, where the seed can be derived from a hash.
+#[derive(Clone)]
+pub struct ShakeRng {
+    reader: XofReader,
+}
+
+impl ShakeRng {
+    /// Create a generator whose output is SHAKE256 of the seed, the seed can be of any type that implements the Input trait.
+    pub fn from_seed_bytes<T: Input>(seed: T) -> ShakeRng {
+        let mut hasher = ShakeHasher::new(256);
+        hasher.update(seed);
+
+        ShakeRng {
+            reader: hasher.finalize_xof(),
+        }
+    }
+
+    /// Mix more seed material into the generator. The new output is SHAKE256 of the next 64 bytes of output followed by the seed, so it depends on both the old state and the new seed.
+    pub fn reseed<T: Input>(&mut self, seed: T) {
+        let mut hasher = ShakeHasher::new(256);
+        hasher.update(self.chain_value());
+        hasher.update(seed);
+        self.reader = hasher.finalize_xof();
+    }
+
+    /// Create an independent child generator for the given label, for example one generator for each part of a simulation. The child is cSHAKE256 of the next 64 bytes of output with the label as customization string, so different labels give unrelated generators and the parent continues with a stream that does not overlap the child.
+    pub fn fork<T: Input>(&mut self, label: T) -> ShakeRng {
+        let mut hasher = CShakeHasher::new(256, "", label);
+        hasher.update(self.chain_value());
+
+        ShakeRng {
+            reader: hasher.finalize_xof(),
+        }
+    }
+
+    /// Read the bytes which are absorbed into the new state when reseeding or forking.
+    fn chain_value(&mut self) -> Vec<u8> {
+        let mut value = vec![0; CHAIN_SIZE];
+        self.reader.squeeze(&mut value);
+        value
+    }
+}
+
+impl RngCore for ShakeRng {
+    fn next_u32(&mut self) -> u32 {
+        impls::next_u32_via_fill(self)
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        impls::next_u64_via_fill(self)
+    }
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        self.reader.squeeze(dest);
+    }
+
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), Error> {
+        self.reader.squeeze(dest);
+        Ok(())
+    }
+}
+
+impl SeedableRng for ShakeRng {
+    type Seed = [u8; 32];
+
+    fn from_seed(seed: [u8; 32]) -> ShakeRng {
+        ShakeRng::from_seed_bytes(seed.as_slice())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::shake::shake256_bytes;
+
+    #[test]
+    fn test_output_is_shake256() {
+        let mut rng = ShakeRng::from_seed_bytes("seed");
+        let mut out = [0; 300];
+        rng.fill_bytes(&mut out[..100]);
+        rng.fill_bytes(&mut out[100..]);
+        assert_eq!(out.to_vec(), shake256_bytes("seed", 8 * 300));
+
+        // Numbers are read as little endian from the same stream.
+        let expected = shake256_bytes("seed", 8 * 12);
+        let mut rng = ShakeRng::from_seed_bytes("seed");
+        assert_eq!(
+            rng.next_u64(),
+            u64::from_le_bytes(expected[..8].try_into().unwrap())
+        );
+        assert_eq!(
+            rng.next_u32(),
+            u32::from_le_bytes(expected[8..].try_into().unwrap())
+        );
+    }
+
+    #[test]
+    fn test_seedable() {
+        let mut a = ShakeRng::from_seed([7; 32]);
+        let mut b = ShakeRng::from_seed_bytes([7; 32].as_slice());
+        assert_eq!(a.next_u64(), b.next_u64());
+
+        let mut c = ShakeRng::seed_from_u64(1);
+        let mut d = ShakeRng::seed_from_u64(1);
+        let mut e = ShakeRng::seed_from_u64(2);
+        let x = c.next_u64();
+        assert_eq!(x, d.next_u64());
+        assert_ne!(x, e.next_u64());
+    }
+
+    #[test]
+    fn test_reseed() {
+        let mut a = ShakeRng::from_seed_bytes("seed");
+        let mut b = ShakeRng::from_seed_bytes("seed");
+        a.reseed("more");
+        b.reseed("more");
+        assert_eq!(a.next_u64(), b.next_u64());
+
+        let mut c = ShakeRng::from_seed_bytes("seed");
+        c.reseed("other");
+        assert_ne!(a.next_u64(), c.next_u64());
+    }
+
+    #[test]
+    fn test_fork() {
+        let mut parent = ShakeRng::from_seed_bytes("seed");
+        let mut copy = parent.clone();
+
+        let mut left = parent.fork("left");
+        let mut right = copy.fork("right");
+        assert_ne!(left.next_u64(), right.next_u64());
+
+        // Forking is deterministic, and the parent is not affected by the label of the child.
+        let mut again = ShakeRng::from_seed_bytes("seed").fork("left");
+        let mut left = ShakeRng::from_seed_bytes("seed").fork("left");
+        assert_eq!(left.next_u64(), again.next_u64());
+        assert_eq!(parent.next_u64(), copy.next_u64());
+    }
+}
@@ -0,0 +1,243 @@
+use crate::keccak::KeccakHasher;
+use crate::sha3::Sha3Hasher;
+use crate::shake::ShakeHasher;
+use crate::sponge::XofReader;
+use digest::consts::{U104, U136, U144, U168, U28, U32, U48, U64, U72};
+use digest::core_api::BlockSizeUser;
+use digest::{
+    ExtendableOutput, ExtendableOutputReset, FixedOutput, FixedOutputReset, HashMarker, Output,
+    OutputSizeUser, Reset, Update,
+};
+
+/// Implement the fixed output traits of the digest crate for a wrapper around one of the hashers, where the block size is the rate in bytes (200 - 2 * size / 8).
+macro_rules! fixed_hasher {
+    ($(#[$doc:meta])* $name:ident, $hasher:ident, $size:literal, $output:ty, $block:ty) => {
+        $(#[$doc])*
+        #[derive(Clone)]
+        pub struct $name {
+            hasher: $hasher,
+        }
+
+        impl Default for $name {
+            fn default() -> $name {
+                $name {
+                    hasher: $hasher::new($size),
+                }
+            }
+        }
+
+        impl HashMarker for $name {}
+
+        impl BlockSizeUser for $name {
+            type BlockSize = $block;
+        }
+
+        impl OutputSizeUser for $name {
+            type OutputSize = $output;
+        }
+
+        impl Update for $name {
+            fn update(&mut self, data: &[u8]) {
+                self.hasher.update(data);
+            }
+        }
+
+        impl FixedOutput for $name {
+            fn finalize_into(self, out: &mut Output<$name>) {
+                out.copy_from_slice(&self.hasher.finalize_bytes());
+            }
+        }
+
+        impl Reset for $name {
+            fn reset(&mut self) {
+                *self = $name::default();
+            }
+        }
+
+        impl FixedOutputReset for $name {
+            fn finalize_into_reset(&mut self, out: &mut Output<$name>) {
+                std::mem::take(self).finalize_into(out);
+            }
+        }
+    };
+}
+
+/// Implement the extendable output traits of the digest crate for a wrapper around the SHAKE hasher, where the block size is the rate in bytes (200 - 2 * capacity / 8).
+macro_rules! xof_hasher {
+    ($(#[$doc:meta])* $name:ident, $capacity:literal, $block:ty) => {
+        $(#[$doc])*
+        #[derive(Clone)]
+        pub struct $name {
+            hasher: ShakeHasher,
+        }
+
+        impl Default for $name {
+            fn default() -> $name {
+                $name {
+                    hasher: ShakeHasher::new($capacity),
+                }
+            }
+        }
+
+        impl HashMarker for $name {}
+
+        impl BlockSizeUser for $name {
+            type BlockSize = $block;
+        }
+
+        impl Update for $name {
+            fn update(&mut self, data: &[u8]) {
+                self.hasher.update(data);
+            }
+        }
+
+        impl ExtendableOutput for $name {
+            type Reader = XofReader;
+
+            fn finalize_xof(self) -> XofReader {
+                self.hasher.finalize_xof()
+            }
+        }
+
+        impl Reset for $name {
+            fn reset(&mut self) {
+                *self = $name::default();
+            }
+        }
+
+        impl ExtendableOutputReset for $name {
+            fn finalize_xof_reset(&mut self) -> XofReader {
+                std::mem::take(self).finalize_xof()
+            }
+        }
+    };
+}
+
+fixed_hasher!(
+    /// SHA3-224 for code which is generic over the traits of the digest crate, like `digest::Digest`.
+    Sha3_224, Sha3Hasher, 224, U28, U144
+);
+fixed_hasher!(
+    /// SHA3-256 for code which is generic over the traits of the digest crate, like `digest::Digest`.
+    Sha3_256, Sha3Hasher, 256, U32, U136
+);
+fixed_hasher!(
+    /// SHA3-384 for code which is generic over the traits of the digest crate, like `digest::Digest`.
+    Sha3_384, Sha3Hasher, 384, U48, U104
+);
+fixed_hasher!(
+    /// SHA3-512 for code which is generic over the traits of the digest crate, like `digest::Digest`.
+    Sha3_512, Sha3Hasher, 512, U64, U72
+);
+fixed_hasher!(
+    /// Keccak-224 with the original padding for code which is generic over the traits of the digest crate.
+    Keccak224, KeccakHasher, 224, U28, U144
+);
+fixed_hasher!(
+    /// Keccak-256 with the original padding for code which is generic over the traits of the digest crate.
+    Keccak256, KeccakHasher, 256, U32, U136
+);
+fixed_hasher!(
+    /// Keccak-384 with the original padding for code which is generic over the traits of the digest crate.
+    Keccak384, KeccakHasher, 384, U48, U104
+);
+fixed_hasher!(
+    /// Keccak-512 with the original padding for code which is generic over the traits of the digest crate.
+    Keccak512, KeccakHasher, 512, U64, U72
+);
+xof_hasher!(
+    /// SHAKE128 for code which is generic over the `ExtendableOutput` trait of the digest crate.
+    Shake128, 128, U168
+);
+xof_hasher!(
+    /// SHAKE256 for code which is generic over the `ExtendableOutput` trait of the digest crate.
+    Shake256, 256, U136
+);
+
+impl digest::XofReader for XofReader {
+    fn read(&mut self, buffer: &mut [u8]) {
+        self.squeeze(buffer);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::hmac::hmac_bytes;
+    use crate::keccak::keccak_bytes;
+    use crate::sha3::sha3_bytes;
+    use crate::shake::shake_bytes;
+    use digest::Digest;
+
+    fn digest<D: Digest>(input: &str) -> Vec<u8> {
+        D::digest(input).to_vec()
+    }
+
+    fn xof<X: ExtendableOutput + Default>(input: &str, length: usize) -> Vec<u8> {
+        let mut out = vec![0; length];
+        X::digest_xof(input, &mut out);
+        out
+    }
+
+    #[test]
+    fn test_digest() {
+        let input = "The quick brown fox jumps over the lazy dog";
+        assert_eq!(digest::<Sha3_224>(input), sha3_bytes(224, input));
+        assert_eq!(digest::<Sha3_256>(input), sha3_bytes(256, input));
+        assert_eq!(digest::<Sha3_384>(input), sha3_bytes(384, input));
+        assert_eq!(digest::<Sha3_512>(input), sha3_bytes(512, input));
+        assert_eq!(digest::<Keccak224>(input), keccak_bytes(224, input));
+        assert_eq!(digest::<Keccak256>(input), keccak_bytes(256, input));
+        assert_eq!(digest::<Keccak384>(input), keccak_bytes(384, input));
+        assert_eq!(digest::<Keccak512>(input), keccak_bytes(512, input));
+
+        // Incremental updates and resetting the hasher.
+        let mut hasher = Sha3_256::new();
+        Digest::update(&mut hasher, "The quick brown fox ");
+        Digest::update(&mut hasher, "jumps over the lazy dog");
+        assert_eq!(hasher.finalize_reset().to_vec(), sha3_bytes(256, input));
+        assert_eq!(hasher.finalize().to_vec(), sha3_bytes(256, ""));
+    }
+
+    #[test]
+    fn test_xof() {
+        let input = "The quick brown fox jumps over the lazy dog";
+        assert_eq!(
+            xof::<Shake128>(input, 300),
+            shake_bytes(128, input, 8 * 300)
+        );
+        assert_eq!(
+            xof::<Shake256>(input, 300),
+            shake_bytes(256, input, 8 * 300)
+        );
+
+        let mut hasher = Shake256::default();
+        hasher.update(input.as_bytes());
+        let mut reader = hasher.finalize_xof_reset();
+        let mut out = [0; 32];
+        digest::XofReader::read(&mut reader, &mut out);
+        assert_eq!(out.to_vec(), shake_bytes(256, input, 256));
+        hasher.finalize_xof().squeeze(&mut out);
+        assert_eq!(out.to_vec(), shake_bytes(256, "", 256));
+    }
+
+    #[test]
+    fn test_generic_hmac() {
+        // The HMAC implementation of the hmac crate is generic over the digest traits, and gives the same result as the HMAC of this crate.
+        use hmac::{Mac, SimpleHmac};
+
+        let mut mac = SimpleHmac::<Sha3_256>::new_from_slice(b"key").unwrap();
+        Mac::update(&mut mac, b"message");
+        assert_eq!(
+            mac.finalize().into_bytes().to_vec(),
+            hmac_bytes(256, "key", "message")
+        );
+
+        let mut mac = SimpleHmac::<Sha3_512>::new_from_slice(&[0x0B; 100]).unwrap();
+        Mac::update(&mut mac, b"message");
+        assert_eq!(
+            mac.finalize().into_bytes().to_vec(),
+            hmac_bytes(512, [0x0B; 100].as_slice(), "message")
+        );
+    }
+}
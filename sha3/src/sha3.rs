@@ -1,16 +1,67 @@
-use crate::input::Input;
-use crate::utils::keccak;
+use crate::error::Error;
+use crate::input::{Input, StreamInput};
+use crate::sponge::Sponge;
+use crate::utils::{append_suffix, bytes_to_hex, check_bit_length, keccak};
+use std::io::{self, Read};
 
-/// The SHA-3 algorithm. Here, the size is the final length of the output in bits (has to be one of the standard sizes 224, 256, 384 or 512) and the input can be of any type that implements the Input trait.
+/// The SHA-3 algorithm. Here, the size is the final length of the output in bits (has to be one of the standard sizes 224, 256, 384 or 512) and the input can be of any type that implements the Input trait. Panics if the size is invalid, use try_sha3 if the size is not known at compile time.
 pub fn sha3<T: Input>(size: usize, input: T) -> String {
-    match size {
-        224 | 256 | 384 | 512 => (),
-        _ => panic!("Invalid size"),
-    }
+    bytes_to_hex(&sha3_bytes(size, input))
+}
+
+/// The SHA-3 algorithm returning the raw bytes of the hash instead of a hex string. The size and input are the same as for the sha3 function.
+pub fn sha3_bytes<T: Input>(size: usize, input: T) -> Vec<u8> {
+    try_sha3_bytes(size, input).unwrap_or_else(|e| panic!("{}", e))
+}
+
+/// Fallible version of the sha3 function, which returns an error instead of panicking when the size is invalid.
+pub fn try_sha3<T: Input>(size: usize, input: T) -> Result<String, Error> {
+    try_sha3_bytes(size, input).map(|hash| bytes_to_hex(&hash))
+}
+
+/// Fallible version of the sha3_bytes function, which returns an error instead of panicking when the size is invalid.
+pub fn try_sha3_bytes<T: Input>(size: usize, input: T) -> Result<Vec<u8>, Error> {
+    check_size(size)?;
 
     let mut m = input.convert().to_vec();
     m.push(0x06); // Add the SHA-3 domain separator and first padding bit
-    keccak(size * 2, size, m)
+    Ok(keccak(size * 2, size, m))
+}
+
+/// The SHA-3 algorithm for messages which are not a whole number of bytes, as used by the bit-oriented test vectors of NIST. The message is the first "bits" bits of the input, where bit i is bit i mod 8 of byte i / 8 counting from the least significant bit like in FIPS 202. Panics if the size or bit length is invalid.
+pub fn sha3_bits<T: Input>(size: usize, input: T, bits: usize) -> String {
+    try_sha3_bits(size, input, bits).unwrap_or_else(|e| panic!("{}", e))
+}
+
+/// Fallible version of the sha3_bits function, which returns an error instead of panicking when the size or bit length is invalid.
+pub fn try_sha3_bits<T: Input>(size: usize, input: T, bits: usize) -> Result<String, Error> {
+    check_size(size)?;
+    check_bit_length(input.convert(), bits)?;
+
+    // Add the SHA-3 domain separator and first padding bit right after the last bit of the message.
+    let m = append_suffix(input.convert(), bits, 0x06);
+    Ok(bytes_to_hex(&keccak(size * 2, size, m)))
+}
+
+/// The SHA-3 hash of everything read from the reader until the end, like a file or network stream. The input is read in chunks through a fixed size buffer with `io::copy`, so it never has to be in memory all at once. Returns an error of the kind `InvalidInput` if the size is invalid, before anything is read, or the error of the reader if reading fails.
+pub fn hash_reader<R: Read>(size: usize, reader: R) -> io::Result<String> {
+    hash_reader_bytes(size, reader).map(|hash| bytes_to_hex(&hash))
+}
+
+/// The hash_reader function returning the raw bytes of the hash instead of a hex string.
+pub fn hash_reader_bytes<R: Read>(size: usize, mut reader: R) -> io::Result<Vec<u8>> {
+    let mut hasher =
+        Sha3Hasher::try_new(size).map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e))?;
+    io::copy(&mut reader, &mut hasher)?;
+    Ok(hasher.finalize_bytes())
+}
+
+/// Check that the size is one of the standard SHA-3 sizes.
+pub fn check_size(size: usize) -> Result<(), Error> {
+    match size {
+        224 | 256 | 384 | 512 => Ok(()),
+        _ => Err(Error::InvalidSize(size)),
+    }
 }
 
 /// Shortcut for the SHA3-224 function, the input can be of any type that implements the Input trait.
@@ -33,6 +84,83 @@ pub fn sha3_512<T: Input>(input: T) -> String {
     sha3(512, input)
 }
 
+/// Shortcut for the SHA3-224 function returning the 28 bytes of the hash.
+pub fn sha3_224_bytes<T: Input>(input: T) -> [u8; 28] {
+    // The length always matches, since the output size is given by the SHA-3 variant.
+    sha3_bytes(224, input).try_into().unwrap()
+}
+
+/// Shortcut for the SHA3-256 function returning the 32 bytes of the hash.
+pub fn sha3_256_bytes<T: Input>(input: T) -> [u8; 32] {
+    sha3_bytes(256, input).try_into().unwrap()
+}
+
+/// Shortcut for the SHA3-384 function returning the 48 bytes of the hash.
+pub fn sha3_384_bytes<T: Input>(input: T) -> [u8; 48] {
+    sha3_bytes(384, input).try_into().unwrap()
+}
+
+/// Shortcut for the SHA3-512 function returning the 64 bytes of the hash.
+pub fn sha3_512_bytes<T: Input>(input: T) -> [u8; 64] {
+    sha3_bytes(512, input).try_into().unwrap()
+}
+
+/// Incremental version of the SHA-3 algorithm for input which is not available all at once, like large files or network streams. The input can be given in any number of parts with `update` and the result is the same as calling `sha3` with all of the input concatenated.
+#[derive(Clone)]
+pub struct Sha3Hasher {
+    sponge: Sponge,
+    size: usize,
+}
+
+impl Sha3Hasher {
+    /// Create a new hasher, the size is the final length of the output in bits (has to be one of the standard sizes 224, 256, 384 or 512). Panics if the size is invalid.
+    pub fn new(size: usize) -> Sha3Hasher {
+        Sha3Hasher::try_new(size).unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    /// Fallible version of new, which returns an error instead of panicking when the size is invalid.
+    pub fn try_new(size: usize) -> Result<Sha3Hasher, Error> {
+        check_size(size)?;
+
+        Ok(Sha3Hasher {
+            sponge: Sponge::new(size * 2),
+            size,
+        })
+    }
+
+    /// Add more input to the hasher, the input can be of any type that implements the Input trait.
+    pub fn update<T: Input>(&mut self, input: T) {
+        self.sponge.update(input.convert());
+    }
+
+    /// Add input which is read in chunks to the hasher, like a file, a path or a reader. Returns an error if reading the input fails.
+    pub fn update_stream<S: StreamInput>(&mut self, input: S) -> io::Result<()> {
+        input.write_to(self)
+    }
+
+    /// Consume the hasher and return the hash of all the input given so far.
+    pub fn finalize(self) -> String {
+        bytes_to_hex(&self.finalize_bytes())
+    }
+
+    /// Consume the hasher and return the raw bytes of the hash of all the input given so far.
+    pub fn finalize_bytes(self) -> Vec<u8> {
+        self.sponge.finalize(0x06, self.size)
+    }
+}
+
+impl io::Write for Sha3Hasher {
+    /// Add the buffer to the input of the hasher, so a file or socket can be hashed with `io::copy`. All of the buffer is always written.
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.update(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -88,4 +216,133 @@ mod tests {
             "01dedd5de4ef14642445ba5f5b97c15e47b9ad931326e4b0727cd94cefc44fff23f07bf543139939b49128caf436dc1bdee54fcb24023a08d9403f9b4bf0d450"
         );
     }
+
+    #[test]
+    fn test_sha3_hasher() {
+        let mut hasher = Sha3Hasher::new(256);
+        hasher.update("The quick brown fox ");
+        hasher.update("jumps over the lazy dog");
+        assert_eq!(
+            hasher.finalize(),
+            "69070dda01975c8c120c3aada1b282394e7f032fa9cf32f4cb2259a0897dfc04"
+        );
+
+        assert_eq!(Sha3Hasher::new(224).finalize(), sha3_224(""));
+    }
+
+    #[test]
+    fn test_sha3_hasher_splits() {
+        let input: Vec<u8> = (0..=255).cycle().take(400).collect();
+
+        for size in [224, 256, 384, 512] {
+            let expected = sha3(size, input.as_slice());
+
+            for split in 0..=input.len() {
+                let mut hasher = Sha3Hasher::new(size);
+                hasher.update(&input[..split]);
+                hasher.update(&input[split..]);
+                assert_eq!(hasher.finalize(), expected);
+            }
+        }
+    }
+
+    #[test]
+    fn test_hash_reader() {
+        let input: Vec<u8> = (0..=255).cycle().take(100000).collect();
+        assert_eq!(
+            hash_reader(256, input.as_slice()).unwrap(),
+            sha3(256, input.as_slice())
+        );
+        assert_eq!(
+            hash_reader_bytes(512, io::empty()).unwrap(),
+            sha3_bytes(512, "")
+        );
+
+        // An invalid size is an error instead of a panic, and nothing is read from the reader.
+        let mut reader = input.as_slice();
+        let err = hash_reader(100, &mut reader).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidInput);
+        assert_eq!(err.to_string(), Error::InvalidSize(100).to_string());
+        assert_eq!(reader.len(), input.len());
+
+        // The hasher can also be written to directly.
+        let mut hasher = Sha3Hasher::new(384);
+        io::Write::write_all(&mut hasher, &input).unwrap();
+        assert_eq!(hasher.finalize(), sha3(384, input));
+    }
+
+    #[test]
+    fn test_sha3_bytes() {
+        assert_eq!(
+            sha3_224_bytes("The quick brown fox jumps over the lazy dog")[..4],
+            [0xd1, 0x5d, 0xad, 0xce]
+        );
+        assert_eq!(
+            bytes_to_hex(&sha3_256_bytes("")),
+            "a7ffc6f8bf1ed76651c14756a061d662f580ff4de43b49fa82d80a4b80f8434a"
+        );
+        assert_eq!(bytes_to_hex(&sha3_384_bytes("abc")), sha3_384("abc"));
+        assert_eq!(bytes_to_hex(&sha3_512_bytes("abc")), sha3_512("abc"));
+        assert_eq!(sha3_bytes(256, "abc").to_vec(), sha3_256_bytes("abc"));
+
+        let mut hasher = Sha3Hasher::new(512);
+        hasher.update("abc");
+        assert_eq!(hasher.finalize_bytes(), sha3_512_bytes("abc"));
+    }
+
+    #[test]
+    fn test_try_sha3() {
+        assert_eq!(try_sha3(256, ""), Ok(sha3_256("")));
+        assert_eq!(
+            try_sha3_bytes(512, "abc"),
+            Ok(sha3_512_bytes("abc").to_vec())
+        );
+        assert_eq!(try_sha3(100, ""), Err(Error::InvalidSize(100)));
+        assert_eq!(try_sha3_bytes(0, ""), Err(Error::InvalidSize(0)));
+        assert!(Sha3Hasher::try_new(224).is_ok());
+        assert_eq!(
+            Sha3Hasher::try_new(128).map(|_| ()),
+            Err(Error::InvalidSize(128))
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "Invalid size 100")]
+    fn test_sha3_invalid_size() {
+        sha3(100, "");
+    }
+
+    #[test]
+    fn test_sha3_bits() {
+        // Test vectors from the NIST examples for messages of 5 and 30 bits.
+        assert_eq!(
+            sha3_bits(224, [0x13].as_slice(), 5),
+            "ffbad5da96bad71789330206dc6768ecaeb1b32dca6b3301489674ab"
+        );
+        assert_eq!(
+            sha3_bits(256, [0x13].as_slice(), 5),
+            "7b0047cf5a456882363cbf0fb05322cf65f4b7059a46365e830132e3b5d957af"
+        );
+        assert_eq!(
+            sha3_bits(512, [0x13].as_slice(), 5),
+            "a13e01494114c09800622a70288c432121ce70039d753cadd2e006e4d961cb27544c1481e5814bdceb53be6733d5e099795e5e81918addb058e22a9f24883f37"
+        );
+        assert_eq!(
+            sha3_bits(224, [0x53, 0x58, 0x7B, 0x19].as_slice(), 30),
+            "d666a514cc9dba25ac1ba69ed3930460deaac9851b5f0baab007df3b"
+        );
+        assert_eq!(
+            sha3_bits(256, [0x53, 0x58, 0x7B, 0x19].as_slice(), 30),
+            "c8242fef409e5ae9d1f1c857ae4dc624b92b19809f62aa8c07411c54a078b1d0"
+        );
+
+        // Whole bytes give the same result as the byte-oriented function.
+        assert_eq!(sha3_bits(384, "abc", 24), sha3_384("abc"));
+        assert_eq!(sha3_bits(256, "abcd", 24), sha3_256("abc"));
+
+        assert_eq!(
+            try_sha3_bits(256, "abc", 25),
+            Err(Error::InvalidBitLength(25))
+        );
+    }
 }
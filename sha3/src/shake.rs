@@ -1,20 +1,70 @@
-use crate::input::Input;
-use crate::utils::keccak;
+use crate::error::Error;
+use crate::input::{Input, StreamInput};
+use crate::sponge::{Sponge, XofReader};
+use crate::utils::{append_suffix, bytes_to_hex, check_bit_length, keccak};
+use std::io;
 
-/// The SHAKE function. Here, the capacity is the number used to decide block length and more (has to be one of the standard sizes 128 or 256). The size is the final length of the output in bits (has to be a number divisible by 8) and the input can be of any type that implements the Input trait.
+/// The SHAKE function. Here, the capacity is the number used to decide block length and more (has to be one of the standard sizes 128 or 256). The size is the final length of the output in bits (has to be a number divisible by 8) and the input can be of any type that implements the Input trait. Panics if the capacity or size is invalid, use try_shake if they are not known at compile time.
 pub fn shake<T: Input>(capacity: usize, input: T, size: usize) -> String {
+    bytes_to_hex(&shake_bytes(capacity, input, size))
+}
+
+/// The SHAKE function returning the raw bytes of the output instead of a hex string. The capacity, input and size are the same as for the shake function.
+pub fn shake_bytes<T: Input>(capacity: usize, input: T, size: usize) -> Vec<u8> {
+    try_shake_bytes(capacity, input, size).unwrap_or_else(|e| panic!("{}", e))
+}
+
+/// Fallible version of the shake function, which returns an error instead of panicking when the capacity or size is invalid.
+pub fn try_shake<T: Input>(capacity: usize, input: T, size: usize) -> Result<String, Error> {
+    try_shake_bytes(capacity, input, size).map(|output| bytes_to_hex(&output))
+}
+
+/// Fallible version of the shake_bytes function, which returns an error instead of panicking when the capacity or size is invalid.
+pub fn try_shake_bytes<T: Input>(capacity: usize, input: T, size: usize) -> Result<Vec<u8>, Error> {
+    check_capacity(capacity)?;
+    check_output_length(size)?;
+
+    let mut m = input.convert().to_vec();
+    m.push(0x1F); // Add the SHAKE domain separator and first padding bit
+    Ok(keccak(capacity * 2, size, m))
+}
+
+/// The SHAKE function for messages which are not a whole number of bytes. The message is the first "bits" bits of the input, numbered like in FIPS 202 (see sha3_bits), and the capacity and size are the same as for the shake function. Panics if the capacity, bit length or size is invalid.
+pub fn shake_bits<T: Input>(capacity: usize, input: T, bits: usize, size: usize) -> String {
+    try_shake_bits(capacity, input, bits, size).unwrap_or_else(|e| panic!("{}", e))
+}
+
+/// Fallible version of the shake_bits function, which returns an error instead of panicking when the capacity, bit length or size is invalid.
+pub fn try_shake_bits<T: Input>(
+    capacity: usize,
+    input: T,
+    bits: usize,
+    size: usize,
+) -> Result<String, Error> {
+    check_capacity(capacity)?;
+    check_output_length(size)?;
+    check_bit_length(input.convert(), bits)?;
+
+    // Add the SHAKE domain separator and first padding bit right after the last bit of the message.
+    let m = append_suffix(input.convert(), bits, 0x1F);
+    Ok(bytes_to_hex(&keccak(capacity * 2, size, m)))
+}
+
+/// Check that the capacity is one of the standard SHAKE capacities.
+pub fn check_capacity(capacity: usize) -> Result<(), Error> {
     match capacity {
-        128 | 256 => (),
-        _ => panic!("Invalid capacity"),
+        128 | 256 => Ok(()),
+        _ => Err(Error::InvalidCapacity(capacity)),
     }
+}
 
-    if size % 8 != 0 {
-        panic!("Invalid size");
+/// Check that the output length in bits is a whole number of bytes.
+pub fn check_output_length(size: usize) -> Result<(), Error> {
+    if !size.is_multiple_of(8) {
+        return Err(Error::InvalidOutputLength(size));
     }
 
-    let mut m = input.convert().to_vec();
-    m.push(0x1F); // Add the SHAKE domain separator and first padding bit
-    keccak(capacity * 2, size, m)
+    Ok(())
 }
 
 /// Shortcut for the SHAKE128 function, the input can be of any type that implements the Input trait and the size is the final length of the output in bits (has to be a number divisible by 8).
@@ -27,6 +77,83 @@ pub fn shake256<T: Input>(input: T, size: usize) -> String {
     shake(256, input, size)
 }
 
+/// Shortcut for the SHAKE128 function returning the raw bytes of the output, the size is in bits like for shake128.
+pub fn shake128_bytes<T: Input>(input: T, size: usize) -> Vec<u8> {
+    shake_bytes(128, input, size)
+}
+
+/// Shortcut for the SHAKE256 function returning the raw bytes of the output, the size is in bits like for shake256.
+pub fn shake256_bytes<T: Input>(input: T, size: usize) -> Vec<u8> {
+    shake_bytes(256, input, size)
+}
+
+/// Incremental version of the SHAKE function for input which is not available all at once. After all of the input has been given with `update`, the output can either be returned with a fixed length or read in chunks of any size from an XofReader.
+#[derive(Clone)]
+pub struct ShakeHasher {
+    sponge: Sponge,
+}
+
+impl ShakeHasher {
+    /// Create a new hasher, the capacity has to be one of the standard sizes 128 or 256. Panics if the capacity is invalid.
+    pub fn new(capacity: usize) -> ShakeHasher {
+        ShakeHasher::try_new(capacity).unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    /// Fallible version of new, which returns an error instead of panicking when the capacity is invalid.
+    pub fn try_new(capacity: usize) -> Result<ShakeHasher, Error> {
+        check_capacity(capacity)?;
+
+        Ok(ShakeHasher {
+            sponge: Sponge::new(capacity * 2),
+        })
+    }
+
+    /// Add more input to the hasher, the input can be of any type that implements the Input trait.
+    pub fn update<T: Input>(&mut self, input: T) {
+        self.sponge.update(input.convert());
+    }
+
+    /// Add input which is read in chunks to the hasher, like a file, a path or a reader. Returns an error if reading the input fails.
+    pub fn update_stream<S: StreamInput>(&mut self, input: S) -> io::Result<()> {
+        input.write_to(self)
+    }
+
+    /// Consume the hasher and return the output with the given size in bits (has to be a number divisible by 8).
+    pub fn finalize(self, size: usize) -> String {
+        bytes_to_hex(&self.finalize_bytes(size))
+    }
+
+    /// Consume the hasher and return the raw bytes of the output with the given size in bits (has to be a number divisible by 8).
+    pub fn finalize_bytes(self, size: usize) -> Vec<u8> {
+        self.try_finalize_bytes(size)
+            .unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    /// Fallible version of finalize_bytes, which returns an error instead of panicking when the size is invalid.
+    pub fn try_finalize_bytes(self, size: usize) -> Result<Vec<u8>, Error> {
+        check_output_length(size)?;
+
+        Ok(self.sponge.finalize(0x1F, size))
+    }
+
+    /// Consume the hasher and return a reader from which any amount of output can be read.
+    pub fn finalize_xof(self) -> XofReader {
+        self.sponge.finalize_xof(0x1F)
+    }
+}
+
+impl io::Write for ShakeHasher {
+    /// Add the buffer to the input of the hasher, so a file or socket can be hashed with `io::copy`. All of the buffer is always written.
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.update(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -56,4 +183,126 @@ mod tests {
             "2f671343d9b2e1604dc9dcf0753e5fe15c7c64a0d283cbbf722d411a0e36f6ca1d01d1369a23539cd80f7c054b6e5daf9c962cad5b8ed5bd11998b40d5734442"
         );
     }
+
+    #[test]
+    fn test_shake_hasher() {
+        let mut hasher = ShakeHasher::new(128);
+        hasher.update("The quick brown fox ");
+        hasher.update("jumps over the lazy dog");
+        assert_eq!(
+            hasher.finalize(256),
+            "f4202e3c5852f9182a0430fd8144f0a74b95e7417ecae17db0f8cfeed0e3e66e"
+        );
+    }
+
+    #[test]
+    fn test_shake_reader() {
+        let expected = shake256("The quick brown fox jumps over the lazy dog", 8 * 500);
+
+        let mut hasher = ShakeHasher::new(256);
+        hasher.update("The quick brown fox jumps over the lazy dog");
+        let mut reader = hasher.finalize_xof();
+
+        // Read the output in uneven chunks which cross the block boundaries.
+        let mut out = Vec::new();
+        for size in [1, 31, 100, 136, 232] {
+            let mut chunk = vec![0; size];
+            reader.squeeze(&mut chunk);
+            out.extend_from_slice(&chunk);
+        }
+        assert_eq!(bytes_to_hex(&out), expected);
+    }
+
+    #[test]
+    fn test_shake_bytes() {
+        assert_eq!(
+            bytes_to_hex(&shake128_bytes("", 256)),
+            "7f9c2ba4e88f827d616045507605853ed73b8093f6efbc88eb1a6eacfa66ef26"
+        );
+        assert_eq!(shake256_bytes("abc", 8 * 100).len(), 100);
+        assert_eq!(
+            bytes_to_hex(&shake256_bytes("abc", 512)),
+            shake256("abc", 512)
+        );
+
+        let mut hasher = ShakeHasher::new(128);
+        hasher.update("abc");
+        assert_eq!(hasher.finalize_bytes(1024), shake128_bytes("abc", 1024));
+    }
+
+    #[test]
+    fn test_try_shake() {
+        assert_eq!(try_shake(128, "", 256), Ok(shake128("", 256)));
+        assert_eq!(
+            try_shake_bytes(256, "abc", 64),
+            Ok(shake256_bytes("abc", 64))
+        );
+        assert_eq!(try_shake(512, "", 256), Err(Error::InvalidCapacity(512)));
+        assert_eq!(try_shake(128, "", 12), Err(Error::InvalidOutputLength(12)));
+        assert!(ShakeHasher::try_new(64).is_err());
+        assert_eq!(
+            ShakeHasher::new(256).try_finalize_bytes(100),
+            Err(Error::InvalidOutputLength(100))
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "Invalid capacity 512")]
+    fn test_shake_invalid_capacity() {
+        shake(512, "", 256);
+    }
+
+    #[test]
+    fn test_shake_bits() {
+        // Test vectors from the NIST examples for messages of 5 and 30 bits, where only the first part of the 4096 bits of output is compared.
+        assert_eq!(
+            shake_bits(128, [0x13].as_slice(), 5, 256),
+            "2e0abfba83e6720bfbc225ff6b7ab9ffce58ba027ee3d898764fef287ddeccca"
+        );
+        assert_eq!(
+            shake_bits(128, [0x53, 0x58, 0x7B, 0x19].as_slice(), 30, 256),
+            "6d5d39c55f3cca567feaf422dc64ba17401d07756d78b0fa3d546d66afc27671"
+        );
+        assert_eq!(
+            shake_bits(256, [0x13].as_slice(), 5, 512),
+            "48a5c11abaeeff092f3646ef0d6b3d3ff76c2f55f9c732ac6470c03764008212e21b1467778b181989f88858211b45df8799cf961f800dfac99e644039e2979a"
+        );
+        assert_eq!(
+            shake_bits(256, [0x53, 0x58, 0x7B, 0x19].as_slice(), 30, 512),
+            "465d081dff875e396200e4481a3e9dcd88d079aa6d66226cb6ba454107cb81a7841ab02960de279ccbe34b42c36585ad86964db0db52b6e7b4369ece8f724858"
+        );
+
+        // Whole bytes give the same result as the byte-oriented function.
+        assert_eq!(shake_bits(128, "abc", 24, 256), shake128("abc", 256));
+        assert_eq!(shake_bits(256, "abcd", 24, 512), shake256("abc", 512));
+
+        // Every message length from 0 to 16 bits gives a different output, including the ones where the suffix spills over into a new byte.
+        let outputs: Vec<String> = (0..=16)
+            .map(|bits| shake_bits(128, [0xFF, 0xFF].as_slice(), bits, 256))
+            .collect();
+        for (i, a) in outputs.iter().enumerate() {
+            for b in &outputs[i + 1..] {
+                assert_ne!(a, b);
+            }
+        }
+
+        assert_eq!(
+            try_shake_bits(256, "", 1, 512),
+            Err(Error::InvalidBitLength(1))
+        );
+    }
+
+    #[test]
+    fn test_shake_io() {
+        let input: Vec<u8> = (0..=255).cycle().take(10000).collect();
+
+        // Copy the input into the hasher and read the output in parts with read_exact.
+        let mut hasher = ShakeHasher::new(256);
+        io::copy(&mut input.as_slice(), &mut hasher).unwrap();
+        let mut reader = hasher.finalize_xof();
+        let mut out = [0; 500];
+        io::Read::read_exact(&mut reader, &mut out[..100]).unwrap();
+        io::Read::read_exact(&mut reader, &mut out[100..]).unwrap();
+        assert_eq!(out.to_vec(), shake_bytes(256, input, 8 * 500));
+    }
 }
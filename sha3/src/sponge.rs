@@ -0,0 +1,203 @@
+use crate::state::State;
+use crate::utils::pad10star1;
+use std::io;
+
+/// A sponge which can absorb input in any number of parts. Data is buffered until a full block of "rate" bytes is available, so the result is the same no matter how the input is split up.
+#[derive(Clone)]
+pub struct Sponge {
+    state: State,
+    // The rate of the sponge in bytes, which is the size of a block.
+    rate: usize,
+    // Input which has not yet filled up a complete block.
+    buffer: Vec<u8>,
+}
+
+impl Sponge {
+    /// Create an empty sponge with the given capacity in bits.
+    pub fn new(capacity: usize) -> Sponge {
+        Sponge::with_rounds(capacity, 24)
+    }
+
+    /// Create an empty sponge with the given capacity in bits, where the permutation only does the given number of rounds.
+    pub fn with_rounds(capacity: usize, rounds: usize) -> Sponge {
+        // Rate is defined as 1600 - capacity in the keccak algorithm used by SHA-3 and SHAKE, divided by 8 to get it in bytes.
+        let rate = (1600 - capacity) / 8;
+
+        Sponge {
+            state: State::with_rounds(rounds),
+            rate,
+            buffer: Vec::with_capacity(rate),
+        }
+    }
+
+    /// The rate of the sponge in bytes.
+    pub fn rate(&self) -> usize {
+        self.rate
+    }
+
+    /// Absorb more input into the sponge. Only complete blocks are absorbed into the state, the rest is kept in the buffer until more input arrives.
+    pub fn update(&mut self, data: &[u8]) {
+        let mut data = data;
+
+        // Fill up the partial block from an earlier update first.
+        if !self.buffer.is_empty() {
+            let needed = (self.rate - self.buffer.len()).min(data.len());
+            self.buffer.extend_from_slice(&data[..needed]);
+            data = &data[needed..];
+
+            if self.buffer.len() == self.rate {
+                self.state.absorb(&self.buffer);
+                self.buffer.clear();
+            }
+        }
+
+        // Absorb whole blocks directly from the input without copying them into the buffer.
+        while data.len() >= self.rate {
+            self.state.absorb(&data[..self.rate]);
+            data = &data[self.rate..];
+        }
+
+        self.buffer.extend_from_slice(data);
+    }
+
+    /// Add the domain separator (including the first padding bit), pad the last block using pad10*1 and squeeze out size bits of output.
+    pub fn finalize(self, suffix: u8, size: usize) -> Vec<u8> {
+        let mut out = vec![0; size / 8];
+        self.finalize_xof(suffix).squeeze(&mut out);
+        out
+    }
+
+    /// Add the domain separator (including the first padding bit), pad the last block using pad10*1 and switch the sponge to the squeezing phase.
+    pub fn finalize_xof(mut self, suffix: u8) -> XofReader {
+        let mut last = std::mem::take(&mut self.buffer);
+        last.push(suffix);
+
+        for block in pad10star1(last, self.rate).chunks(self.rate) {
+            self.state.absorb(block);
+        }
+
+        XofReader {
+            block: self.state.output_block(self.rate * 8),
+            state: self.state,
+            rate: self.rate,
+            position: 0,
+        }
+    }
+}
+
+/// Reader for the output of an extendable-output function (XOF) like SHAKE. The output can be read in chunks of any size and the state is only permuted when the current block of output has been used up, so there is no need to know the total length of the output in advance.
+#[derive(Clone)]
+pub struct XofReader {
+    state: State,
+    rate: usize,
+    // The current block of output and how much of it has been read.
+    block: Vec<u8>,
+    position: usize,
+}
+
+impl XofReader {
+    /// Fill the buffer with the next bytes of output.
+    pub fn squeeze(&mut self, buffer: &mut [u8]) {
+        let mut filled = 0;
+
+        while filled < buffer.len() {
+            // Squeeze out a new block when the current one is used up, in accordance with step 10 of section 5 of FIPS 202.
+            if self.position == self.rate {
+                self.state.permute();
+                self.block = self.state.output_block(self.rate * 8);
+                self.position = 0;
+            }
+
+            let n = (self.rate - self.position).min(buffer.len() - filled);
+            buffer[filled..filled + n]
+                .copy_from_slice(&self.block[self.position..self.position + n]);
+            self.position += n;
+            filled += n;
+        }
+    }
+}
+
+impl io::Read for XofReader {
+    /// Fill the buffer with the next bytes of output, so the output can be used with `read_exact` or `io::copy`. The output never ends, so all of the buffer is always filled.
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        self.squeeze(buf);
+        Ok(buf.len())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::keccak;
+
+    #[test]
+    fn test_update_matches_keccak() {
+        let input: Vec<u8> = (0..=255).cycle().take(500).collect();
+
+        let mut m = input.clone();
+        m.push(0x06);
+        let expected = keccak(512, 256, m);
+
+        // Split the input in every possible place, including both ends and block boundaries.
+        for split in 0..=input.len() {
+            let mut sponge = Sponge::new(512);
+            sponge.update(&input[..split]);
+            sponge.update(&input[split..]);
+            assert_eq!(sponge.finalize(0x06, 256), expected);
+        }
+    }
+
+    #[test]
+    fn test_update_small_chunks() {
+        let input: Vec<u8> = (0..=255).cycle().take(1000).collect();
+
+        let mut m = input.clone();
+        m.push(0x1F);
+        let expected = keccak(256, 512, m);
+
+        for chunk_size in [1, 7, 135, 136, 137, 1000] {
+            let mut sponge = Sponge::new(256);
+            for chunk in input.chunks(chunk_size) {
+                sponge.update(chunk);
+            }
+            assert_eq!(sponge.finalize(0x1F, 512), expected);
+        }
+    }
+
+    #[test]
+    fn test_xof_reader_chunks() {
+        let mut m = b"The quick brown fox jumps over the lazy dog".to_vec();
+        m.push(0x1F);
+        let expected = keccak(256, 8 * 1000, m);
+
+        for chunk_size in [1, 13, 136, 137, 1000] {
+            let mut sponge = Sponge::new(256);
+            sponge.update(b"The quick brown fox jumps over the lazy dog");
+            let mut reader = sponge.finalize_xof(0x1F);
+
+            let mut out = vec![0; 1000];
+            for chunk in out.chunks_mut(chunk_size) {
+                reader.squeeze(chunk);
+            }
+            assert_eq!(out, expected);
+        }
+    }
+
+    #[test]
+    fn test_xof_reader_io() {
+        use std::io::Read;
+
+        let mut m = b"abc".to_vec();
+        m.push(0x1F);
+        let expected = keccak(256, 8 * 200, m);
+
+        // With io::Read in scope, read is the method of the trait and returns the number of bytes.
+        let mut sponge = Sponge::new(256);
+        sponge.update(b"abc");
+        let mut reader = sponge.finalize_xof(0x1F);
+        let mut out = vec![0; 200];
+        assert_eq!(reader.read(&mut out[..50]).unwrap(), 50);
+        reader.read_exact(&mut out[50..]).unwrap();
+        assert_eq!(out, expected);
+    }
+}
@@ -0,0 +1,320 @@
+use crate::duplex::Duplex;
+use crate::error::Error;
+use crate::input::Input;
+use crate::shake::check_output_length;
+use crate::utils::constant_time_eq;
+
+/// The shortest tag in bits which can be wrapped and unwrapped, since shorter tags are too easy to guess.
+const MIN_TAG_SIZE: usize = 32;
+
+/// Suffix byte for a block followed by the frame bit 0 and the first padding bit.
+const FRAME_0: u8 = 0x02;
+/// Suffix byte for a block followed by the frame bit 1 and the first padding bit.
+const FRAME_1: u8 = 0x03;
+
+/// The SpongeWrap authenticated encryption mode from "Duplexing the sponge" by the Keccak team, built on the duplex construction. The key is absorbed when the SpongeWrap is created, and after that it can wrap (encrypt and authenticate) or unwrap (decrypt and verify) a sequence of messages with associated data. Each message is bound to all the messages before it, so both sides have to wrap and unwrap the same messages in the same order. The key has to be used for only one sequence of messages, or a unique nonce has to be part of the key.
+#[derive(Clone)]
+pub struct SpongeWrap {
+    duplex: Duplex,
+    // The size of the blocks the data is split into in bytes, which leaves room for the frame bit and padding in each duplexing call.
+    block_size: usize,
+}
+
+impl SpongeWrap {
+    /// Create a new SpongeWrap with the given capacity in bits and key, which can be of any type that implements the Input trait. Panics if the rate (1600 - capacity) is not a positive multiple of 64.
+    pub fn new<K: Input>(capacity: usize, key: K) -> SpongeWrap {
+        SpongeWrap::try_new(capacity, key).unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    /// Fallible version of new, which returns an error instead of panicking when the capacity is invalid.
+    pub fn try_new<K: Input>(capacity: usize, key: K) -> Result<SpongeWrap, Error> {
+        let duplex = Duplex::try_new(capacity)?;
+        let block_size = duplex.rate() - 1;
+        let mut wrap = SpongeWrap { duplex, block_size };
+
+        // The frame bit is 1 for every block of the key except the last one.
+        let key = blocks(key.convert(), block_size);
+        for (i, block) in key.iter().enumerate() {
+            let frame = if i + 1 < key.len() { FRAME_1 } else { FRAME_0 };
+            wrap.duplex.duplex(block, frame, 0);
+        }
+
+        Ok(wrap)
+    }
+
+    /// Encrypt the plaintext and authenticate it together with the associated data, which is not encrypted. Returns the ciphertext, which has the same length as the plaintext, and a tag with the given size in bits (has to be a number divisible by 8 and at least 32). Panics if the tag size is invalid.
+    pub fn wrap<A: Input, B: Input>(
+        &mut self,
+        associated_data: A,
+        plaintext: B,
+        tag_size: usize,
+    ) -> (Vec<u8>, Vec<u8>) {
+        self.try_wrap(associated_data, plaintext, tag_size)
+            .unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    /// Fallible version of wrap, which returns an error instead of panicking when the tag size is invalid.
+    pub fn try_wrap<A: Input, B: Input>(
+        &mut self,
+        associated_data: A,
+        plaintext: B,
+        tag_size: usize,
+    ) -> Result<(Vec<u8>, Vec<u8>), Error> {
+        check_tag_size(tag_size)?;
+
+        let plaintext = blocks(plaintext.convert(), self.block_size);
+        let mut key_stream = self.absorb_associated_data(associated_data.convert(), plaintext[0]);
+        let mut ciphertext = Vec::new();
+
+        // Each block of plaintext is encrypted with output of the duplexing call which absorbs the block before it.
+        for (i, block) in plaintext.iter().enumerate() {
+            ciphertext.extend(xor(block, &key_stream));
+
+            if let Some(next) = plaintext.get(i + 1) {
+                key_stream = self.duplex.duplex(block, FRAME_1, next.len() * 8);
+            }
+        }
+
+        let tag = self.squeeze_tag(plaintext[plaintext.len() - 1], tag_size);
+        Ok((ciphertext, tag))
+    }
+
+    /// Decrypt the ciphertext and check the tag for it and the associated data in constant time. The tag size is the expected size of the tag in bits (has to be a number divisible by 8 and at least 32), which is usually the size given to wrap. Returns the plaintext, or an InvalidTag error without any of the plaintext when the ciphertext, associated data or tag has been modified, or when the tag does not have the expected size. The SpongeWrap can not be used for more messages after the tag was invalid, since its state no longer matches the state of the sender.
+    pub fn unwrap<A: Input, C: Input>(
+        &mut self,
+        associated_data: A,
+        ciphertext: C,
+        tag: &[u8],
+        tag_size: usize,
+    ) -> Result<Vec<u8>, Error> {
+        check_tag_size(tag_size)?;
+
+        // The size of the tag is not taken from the tag itself, since an empty or truncated tag would then be easy to forge.
+        if tag.len() * 8 != tag_size {
+            return Err(Error::InvalidTag);
+        }
+
+        let ciphertext = blocks(ciphertext.convert(), self.block_size);
+        let mut key_stream = self.absorb_associated_data(associated_data.convert(), ciphertext[0]);
+        let mut plaintext = Vec::new();
+        let mut last = Vec::new();
+
+        // The duplexing calls absorb the plaintext, so each block has to be decrypted before the next key stream can be computed.
+        for (i, block) in ciphertext.iter().enumerate() {
+            last = xor(block, &key_stream);
+
+            if let Some(next) = ciphertext.get(i + 1) {
+                key_stream = self.duplex.duplex(&last, FRAME_1, next.len() * 8);
+            }
+
+            plaintext.extend_from_slice(&last);
+        }
+
+        if !constant_time_eq(&self.squeeze_tag(&last, tag_size), tag) {
+            return Err(Error::InvalidTag);
+        }
+
+        Ok(plaintext)
+    }
+
+    /// Absorb all blocks of the associated data, and return the key stream for the first block of the message. The frame bit is 0 for every block of the associated data except the last one.
+    fn absorb_associated_data(&mut self, associated_data: &[u8], first: &[u8]) -> Vec<u8> {
+        let blocks = blocks(associated_data, self.block_size);
+        let (last, rest) = blocks.split_last().unwrap();
+
+        for block in rest {
+            self.duplex.duplex(block, FRAME_0, 0);
+        }
+
+        self.duplex.duplex(last, FRAME_1, first.len() * 8)
+    }
+
+    /// Absorb the last block of the message and squeeze out a tag with the given size in bits, with extra duplexing calls on empty blocks when the tag is longer than a block.
+    fn squeeze_tag(&mut self, last: &[u8], tag_size: usize) -> Vec<u8> {
+        let mut tag = self.duplex.duplex(last, FRAME_0, self.block_size * 8);
+
+        while tag.len() < tag_size / 8 {
+            tag.extend(self.duplex.duplex(&[], FRAME_0, self.block_size * 8));
+        }
+
+        tag.truncate(tag_size / 8);
+        tag
+    }
+}
+
+/// Check that the tag size is a whole number of bytes and not shorter than the minimum tag size.
+fn check_tag_size(tag_size: usize) -> Result<(), Error> {
+    check_output_length(tag_size)?;
+    if tag_size < MIN_TAG_SIZE {
+        return Err(Error::InvalidTagSize(tag_size));
+    }
+
+    Ok(())
+}
+
+/// Split the data into blocks of the given size, where the last block can be shorter. Empty data is a single empty block, since every part of the input takes at least one duplexing call.
+fn blocks(data: &[u8], block_size: usize) -> Vec<&[u8]> {
+    if data.is_empty() {
+        return vec![data];
+    }
+
+    data.chunks(block_size).collect()
+}
+
+/// XOR the data with the key stream, which is at least as long as the data.
+fn xor(data: &[u8], key_stream: &[u8]) -> Vec<u8> {
+    data.iter().zip(key_stream).map(|(a, b)| a ^ b).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::builder::KeccakBuilder;
+
+    #[test]
+    fn test_wrap_unwrap() {
+        let mut sender = SpongeWrap::new(256, "key");
+        let mut receiver = SpongeWrap::new(256, "key");
+
+        // Messages of different lengths, including empty ones and ones with several blocks.
+        for length in [0, 1, 166, 167, 168, 1000] {
+            let plaintext: Vec<u8> = (0..length).map(|i| i as u8).collect();
+            let (ciphertext, tag) = sender.wrap("header", plaintext.as_slice(), 128);
+
+            assert_eq!(ciphertext.len(), plaintext.len());
+            assert_eq!(tag.len(), 16);
+            if length > 0 {
+                assert_ne!(ciphertext, plaintext);
+            }
+            assert_eq!(
+                receiver.unwrap("header", ciphertext, &tag, 128),
+                Ok(plaintext)
+            );
+        }
+
+        // The state depends on all earlier messages, so the same message gives a different ciphertext.
+        let (first, _) = SpongeWrap::new(256, "key").wrap("", "message", 128);
+        let (second, _) = sender.wrap("", "message", 128);
+        assert_ne!(first, second);
+    }
+
+    #[test]
+    fn test_first_block() {
+        // The key stream for the first block is the output of the Keccak sponge on the padded key block followed by the associated data and the frame bit 1.
+        let mut m = b"key".to_vec();
+        m.push(FRAME_0);
+        m.resize(168, 0x00);
+        m[167] ^= 0x80;
+        m.extend_from_slice(b"header");
+
+        let mut sponge = KeccakBuilder::new(256).suffix(0b1, 1).build().unwrap();
+        sponge.update(m);
+        let key_stream = sponge.finalize_bytes(5 * 8);
+
+        let (ciphertext, _) = SpongeWrap::new(256, "key").wrap("header", "hello", 128);
+        assert_eq!(ciphertext, xor(b"hello", &key_stream));
+    }
+
+    #[test]
+    fn test_long_tag() {
+        let (_, tag) = SpongeWrap::new(256, "key").wrap("", "", 8 * 400);
+        let (_, short) = SpongeWrap::new(256, "key").wrap("", "", 8 * 16);
+        assert_eq!(tag.len(), 400);
+        assert_eq!(tag[..16], short);
+    }
+
+    #[test]
+    fn test_reject_modified() {
+        let (ciphertext, tag) = SpongeWrap::new(512, "key").wrap("header", "attack at dawn", 256);
+        let unwrap = |key: &str, header: &str, ciphertext: &[u8], tag: &[u8]| {
+            SpongeWrap::new(512, key).unwrap(header, ciphertext, tag, 256)
+        };
+
+        assert!(unwrap("key", "header", &ciphertext, &tag).is_ok());
+
+        let mut modified = ciphertext.clone();
+        modified[0] ^= 0x01;
+        assert_eq!(
+            unwrap("key", "header", &modified, &tag),
+            Err(Error::InvalidTag)
+        );
+
+        let mut modified = tag.clone();
+        modified[31] ^= 0x80;
+        assert_eq!(
+            unwrap("key", "header", &ciphertext, &modified),
+            Err(Error::InvalidTag)
+        );
+
+        assert_eq!(
+            unwrap("key", "Header", &ciphertext, &tag),
+            Err(Error::InvalidTag)
+        );
+        assert_eq!(
+            unwrap("kez", "header", &ciphertext, &tag),
+            Err(Error::InvalidTag)
+        );
+        assert_eq!(
+            unwrap("key", "header", &ciphertext[..13], &tag),
+            Err(Error::InvalidTag)
+        );
+    }
+
+    #[test]
+    fn test_reject_tag_size() {
+        let (ciphertext, tag) = SpongeWrap::new(256, "key").wrap("header", "attack at dawn", 128);
+        let (forged, _) = SpongeWrap::new(256, "key").wrap("other", "retreat at dusk", 128);
+        let unwrap = |header: &str, ciphertext: &[u8], tag: &[u8], tag_size: usize| {
+            SpongeWrap::new(256, "key").unwrap(header, ciphertext, tag, tag_size)
+        };
+
+        // An empty tag is rejected for any ciphertext and associated data, and expected sizes shorter than 32 bits are not allowed.
+        assert_eq!(unwrap("other", &forged, &[], 128), Err(Error::InvalidTag));
+        assert_eq!(
+            unwrap("other", &forged, &[], 0),
+            Err(Error::InvalidTagSize(0))
+        );
+        assert_eq!(
+            unwrap("header", &ciphertext, &tag[..3], 24),
+            Err(Error::InvalidTagSize(24))
+        );
+
+        // A truncated tag is rejected even though it is a prefix of the correct tag.
+        assert_eq!(
+            unwrap("header", &ciphertext, &tag[..8], 128),
+            Err(Error::InvalidTag)
+        );
+        assert_eq!(
+            unwrap("header", &ciphertext, &tag, 256),
+            Err(Error::InvalidTag)
+        );
+        assert_eq!(
+            unwrap("header", &ciphertext, &tag, 12),
+            Err(Error::InvalidOutputLength(12))
+        );
+        assert!(unwrap("header", &ciphertext, &tag, 128).is_ok());
+    }
+
+    #[test]
+    fn test_try_wrap() {
+        assert_eq!(
+            SpongeWrap::new(256, "key").try_wrap("", "", 12),
+            Err(Error::InvalidOutputLength(12))
+        );
+
+        // Tags which unwrap would reject because they are too short can not be wrapped either.
+        assert_eq!(
+            SpongeWrap::new(256, "key").try_wrap("", "", 0),
+            Err(Error::InvalidTagSize(0))
+        );
+        assert_eq!(
+            SpongeWrap::new(256, "key").try_wrap("", "", 24),
+            Err(Error::InvalidTagSize(24))
+        );
+        assert!(SpongeWrap::new(256, "key").try_wrap("", "", 32).is_ok());
+        assert_eq!(
+            SpongeWrap::try_new(100, "key").err(),
+            Some(Error::InvalidSpongeCapacity(100))
+        );
+    }
+}
@@ -1,3 +1,5 @@
+use crate::lane::Lane;
+
 /// Offsets for the rho step mapping as defined in table 2 of FIPS 202, the rho offsets have been minimized by doing "mod 64" on all of them.
 const RHO_OFFSETS: [[u32; 5]; 5] = [
     [0, 36, 3, 41, 18],
@@ -35,53 +37,119 @@ const ROUND_CONSTANTS: [u64; 24] = [
     0x8000000080008008,
 ];
 
-pub struct State {
-    state: [[u64; 5]; 5],
+/// The Keccak-p permutation and its state of 5x5 lanes, which everything else in this crate is built on. It can be used directly to build other sponge-based constructions, like duplex objects or custom XOFs. The lanes are u64 by default, which gives Keccak-f\[1600\] as used by SHA-3, but any type implementing the Lane trait can be used to get the smaller widths from section 3 of FIPS 202. Lanes are indexed by x and y like in section 3.1 of FIPS 202, and bits are mapped to the state like in section 3.1.2, so bit i of the state is bit i mod w of lane i / w (where lane i has x = i mod 5 and y = i / 5) and bytes are in little endian order.
+#[derive(Clone)]
+pub struct State<L: Lane = u64> {
+    state: [[L; 5]; 5],
+    // The number of rounds in each permutation, which is 12 + 2l for Keccak-f[b].
+    rounds: usize,
+}
+
+impl Default for State {
+    fn default() -> State {
+        State::new()
+    }
 }
 
 impl State {
+    /// Create an all-zero state for Keccak-f\[1600\] with the full 24 rounds.
     pub fn new() -> State {
-        State { state: [[0; 5]; 5] }
+        State::keccak_f()
     }
 
-    /// Absorb a block of data into the state and apply the permutation.
-    pub fn absorb(&mut self, data: Vec<u8>) {
-        // Breaking the data up into 8 chunks with the size of a byte to get 64 bit words.
-        for (i, chunk) in data.chunks(8).enumerate() {
-            let mut word = 0;
-            for (j, byte) in chunk.iter().enumerate() {
-                // Byte 0-7 will be used like this: 7 || 6 || 5 || 4 || 3 || 2 || 1 || 0. So we are using little endian arrangement of the bytes where z=0 is the least significant bit of the first byte. This is in accordance with the standard where bit strings are reversed compared to the bytes they represent.
-                // OR the byte into the word, and use the index to left shift it.
-                word |= (*byte as u64) << (8 * j);
-            }
-            self.state[i % 5][i / 5] ^= word;
+    /// Create an all-zero state for Keccak-p[1600, n_r], where each permutation only does the last "rounds" rounds of Keccak-f\[1600\]. This is used by TurboSHAKE and KangarooTwelve with 12 rounds. Panics if there are more than 24 rounds.
+    pub fn with_rounds(rounds: usize) -> State {
+        State::keccak_p(rounds)
+    }
+}
+
+impl<L: Lane> State<L> {
+    /// The width b of the permutation in bits, which is 25 times the size of a lane.
+    pub const WIDTH: usize = 25 * L::BITS as usize;
+
+    /// The number of rounds 12 + 2l of Keccak-f\[b\] as defined in section 3.4 of FIPS 202, where the lanes have 2^l bits.
+    pub const MAX_ROUNDS: usize = 12 + 2 * L::BITS.trailing_zeros() as usize;
+
+    /// Create an all-zero state for Keccak-f\[b\] with the full 12 + 2l rounds, where the width depends on the type of the lanes.
+    pub fn keccak_f() -> State<L> {
+        State::keccak_p(State::<L>::MAX_ROUNDS)
+    }
+
+    /// Create an all-zero state for Keccak-p[b, n_r] as defined in section 3.3 of FIPS 202, where each permutation only does the last "rounds" rounds of Keccak-f\[b\]. Panics if there are more rounds than in Keccak-f\[b\].
+    pub fn keccak_p(rounds: usize) -> State<L> {
+        assert!(
+            L::BITS.is_power_of_two() && L::BITS <= 64,
+            "Invalid lane size"
+        );
+        assert!(rounds <= State::<L>::MAX_ROUNDS, "Invalid number of rounds");
+
+        State {
+            state: [[L::default(); 5]; 5],
+            rounds,
         }
+    }
 
-        self.permute();
+    /// The number of rounds in each permutation.
+    pub fn rounds(&self) -> usize {
+        self.rounds
     }
 
-    /// Output a string from a sponge state in accordance with step 7-10 of section 5 of FIPS 202. First get a "rate" size block from the state, add it to the output and do a permutation until the wanted size is achieved.
-    pub fn squeeze(&mut self, rate: usize, size: usize) -> Vec<u8> {
-        let mut out = Vec::new();
+    /// Get the lane at position x, y (both from 0 to 4).
+    pub fn lane(&self, x: usize, y: usize) -> L {
+        self.state[x][y]
+    }
 
-        while out.len() < size / 8 {
-            // Truncate the state to the size of the rate, dividing by 64 since a word is 64 bits (rate is always divisible by 64).
-            for i in 0..(rate / 64) {
-                let word = self.state[i % 5][i / 5];
-                // LE because we save the state in little endian.
-                out.extend_from_slice(&word.to_le_bytes());
-            }
+    /// Set the lane at position x, y (both from 0 to 4).
+    pub fn set_lane(&mut self, x: usize, y: usize, lane: L) {
+        self.state[x][y] = lane;
+    }
 
-            self.permute();
+    /// XOR the bytes into the state starting at the given byte offset, which is how input is absorbed into the rate of a sponge. For the widths 25, 50 and 100 the state does not end on a whole byte, so the unused bits of the last byte have to be 0. Panics if the bytes go past the end of the state.
+    pub fn xor_bytes(&mut self, offset: usize, data: &[u8]) {
+        assert!(
+            offset + data.len() <= State::<L>::WIDTH.div_ceil(8),
+            "Too many bytes for the state"
+        );
+
+        for (i, byte) in data.iter().enumerate() {
+            for bit in 0..8 {
+                if (byte >> bit) & 1 == 0 {
+                    continue;
+                }
+
+                let position = 8 * (offset + i) + bit;
+                assert!(position < State::<L>::WIDTH, "Too many bits for the state");
+                let (lane, z) = (position / L::BITS as usize, position % L::BITS as usize);
+                self.state[lane % 5][lane / 5] ^= L::from_u64(1 << z);
+            }
         }
+    }
 
-        // Only output the needed size
-        out[0..size / 8].to_vec()
+    /// Fill the buffer with the bytes of the state starting at the given byte offset, which is how output is squeezed from the rate of a sponge. The bits after the end of the state are 0. Panics if the buffer goes past the end of the state.
+    pub fn extract_bytes(&self, offset: usize, buffer: &mut [u8]) {
+        assert!(
+            offset + buffer.len() <= State::<L>::WIDTH.div_ceil(8),
+            "Too many bytes for the state"
+        );
+
+        for (i, byte) in buffer.iter_mut().enumerate() {
+            *byte = 0;
+
+            for bit in 0..8 {
+                let position = 8 * (offset + i) + bit;
+                if position == State::<L>::WIDTH {
+                    break;
+                }
+
+                let (lane, z) = (position / L::BITS as usize, position % L::BITS as usize);
+                *byte |= (((self.state[lane % 5][lane / 5].to_u64() >> z) & 1) as u8) << bit;
+            }
+        }
     }
 
-    /// Do a full keccak permutation (24 rounds)
-    fn permute(&mut self) {
-        for i in 0..24 {
+    /// Do a keccak permutation, which is the full 12 + 2l rounds unless the state was created with fewer rounds. The rounds of Keccak-p[b, n_r] are numbered from 12 + 2l - n_r to 12 + 2l - 1 so that the last round is always the same (step 2 of algorithm 7 in FIPS 202).
+    pub fn permute(&mut self) {
+        for i in (State::<L>::MAX_ROUNDS - self.rounds)..State::<L>::MAX_ROUNDS {
             self.round(i);
         }
     }
@@ -98,7 +166,7 @@ impl State {
     /// The theta step mapping as defined in section 3.2.1 of FIPS 202.
     #[allow(clippy::needless_range_loop)]
     fn theta(&mut self) {
-        let mut c = [0; 5];
+        let mut c = [L::default(); 5];
 
         for x in 0..5 {
             c[x] = self.state[x][0]
@@ -108,7 +176,7 @@ impl State {
                 ^ self.state[x][4];
         }
 
-        let mut d = [0; 5];
+        let mut d = [L::default(); 5];
 
         for x in 0..5 {
             // Add 4 instead of subtracting 1 to avoid overflow. "x + 4 mod 5" is the same as "x - 1 mod 5".
@@ -122,12 +190,12 @@ impl State {
         }
     }
 
-    /// The rho step mapping as defined in section 3.2.2 of FIPS 202, but with the offsets precalculated.
+    /// The rho step mapping as defined in section 3.2.2 of FIPS 202, but with the offsets precalculated. The offsets are reduced modulo the size of the lane.
     #[allow(clippy::needless_range_loop)]
     fn rho(&mut self) {
         for x in 0..5 {
             for y in 0..5 {
-                self.state[x][y] = self.state[x][y].rotate_left(RHO_OFFSETS[x][y]);
+                self.state[x][y] = self.state[x][y].rotate_left(RHO_OFFSETS[x][y] % L::BITS);
             }
         }
     }
@@ -145,6 +213,7 @@ impl State {
     }
 
     /// The chi step mapping as defined in section 3.2.4 of FIPS 202.
+    #[allow(clippy::needless_range_loop)]
     fn chi(&mut self) {
         let tmp = self.state;
 
@@ -156,8 +225,381 @@ impl State {
         }
     }
 
-    /// The iota step mapping as defined in section 3.2.5 of FIPS 202, but with the round constants precalculated.
+    /// The iota step mapping as defined in section 3.2.5 of FIPS 202, but with the round constants precalculated. Only bits 2^j - 1 for j <= l of a round constant can be set, so the constants of Keccak-f\[1600\] truncated to the size of the lane are the constants for the smaller widths.
     fn iota(&mut self, round: usize) {
-        self.state[0][0] ^= ROUND_CONSTANTS[round];
+        self.state[0][0] ^= L::from_u64(ROUND_CONSTANTS[round]);
+    }
+}
+
+impl State {
+    /// Absorb a block of data into the state and apply the permutation.
+    pub(crate) fn absorb(&mut self, data: &[u8]) {
+        // Breaking the data up into 8 chunks with the size of a byte to get 64 bit words.
+        for (i, chunk) in data.chunks(8).enumerate() {
+            let mut word = 0;
+            for (j, byte) in chunk.iter().enumerate() {
+                // Byte 0-7 will be used like this: 7 || 6 || 5 || 4 || 3 || 2 || 1 || 0. So we are using little endian arrangement of the bytes where z=0 is the least significant bit of the first byte. This is in accordance with the standard where bit strings are reversed compared to the bytes they represent.
+                // OR the byte into the word, and use the index to left shift it.
+                word |= (*byte as u64) << (8 * j);
+            }
+            self.state[i % 5][i / 5] ^= word;
+        }
+
+        self.permute();
+    }
+
+    /// Output a string from a sponge state in accordance with step 7-10 of section 5 of FIPS 202. First get a "rate" size block from the state, add it to the output and do a permutation until the wanted size is achieved.
+    pub(crate) fn squeeze(&mut self, rate: usize, size: usize) -> Vec<u8> {
+        let mut out = Vec::new();
+
+        while out.len() < size / 8 {
+            out.extend_from_slice(&self.output_block(rate));
+            self.permute();
+        }
+
+        // Only output the needed size
+        out[0..size / 8].to_vec()
+    }
+
+    /// Get the first "rate" bits of the state as bytes, which is one block of output in the squeezing phase.
+    pub(crate) fn output_block(&self, rate: usize) -> Vec<u8> {
+        let mut block = Vec::with_capacity(rate / 8);
+
+        // Truncate the state to the size of the rate, dividing by 64 since a word is 64 bits (rate is always divisible by 64).
+        for i in 0..(rate / 64) {
+            let word = self.state[i % 5][i / 5];
+            // LE because we save the state in little endian.
+            block.extend_from_slice(&word.to_le_bytes());
+        }
+
+        block
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::lane::SmallLane;
+
+    #[test]
+    fn test_permute() {
+        // Known answers for Keccak-f[1600] applied to the all-zero state, from the intermediate values published by the Keccak team.
+        let mut state = State::new();
+        state.permute();
+        assert_eq!(state.lane(0, 0), 0xF1258F7940E1DDE7);
+        assert_eq!(state.lane(1, 0), 0x84D5CCF933C0478A);
+        assert_eq!(state.lane(2, 0), 0xD598261EA65AA9EE);
+        assert_eq!(state.lane(3, 0), 0xBD1547306F80494D);
+
+        state.permute();
+        assert_eq!(state.lane(0, 0), 0x2D5C954DF96ECB3C);
+    }
+
+    /// All lanes of the state in the order of the bits, so lane i has x = i mod 5 and y = i / 5.
+    fn lanes<L: Lane>(state: &State<L>) -> Vec<u64> {
+        (0..25).map(|i| state.lane(i % 5, i / 5).to_u64()).collect()
+    }
+
+    // Known answers for Keccak-f[b] applied twice to the all-zero state, from the intermediate values published by the Keccak team in KeccakF-200-IntermediateValues.txt, KeccakF-400-IntermediateValues.txt and KeccakF-800-IntermediateValues.txt.
+    const KECCAK_F200: [[u64; 25]; 2] = [
+        [
+            0x3C, 0x28, 0x26, 0x84, 0x1C, 0xB3, 0x5C, 0x17, 0x1E, 0xAA, 0xE9, 0xB8, 0x11, 0x13,
+            0x4C, 0xEA, 0xA3, 0x85, 0x2C, 0x69, 0xD2, 0xC5, 0xAB, 0xAF, 0xEA,
+        ],
+        [
+            0x1B, 0xEF, 0x68, 0x94, 0x92, 0xA8, 0xA5, 0x43, 0xA5, 0x99, 0x9F, 0xDB, 0x83, 0x4E,
+            0x31, 0x66, 0xA1, 0x4B, 0xE8, 0x27, 0xD9, 0x50, 0x40, 0x47, 0x9E,
+        ],
+    ];
+    const KECCAK_F400: [[u64; 25]; 2] = [
+        [
+            0x09F5, 0x40AC, 0x0FA9, 0x14F5, 0xE89F, 0xECA0, 0x5BD1, 0x7870, 0xEFF0, 0xBF8F, 0x0337,
+            0x6052, 0xDC75, 0x0EC9, 0xE776, 0x5246, 0x59A1, 0x5D81, 0x6D95, 0x6E14, 0x633E, 0x58EE,
+            0x71FF, 0x714C, 0xB38E,
+        ],
+        [
+            0xE537, 0xD5D6, 0xDBE7, 0xAAF3, 0x9BC7, 0xCA7D, 0x86B2, 0xFDEC, 0x692C, 0x4E5B, 0x67B1,
+            0x15AD, 0xA7F7, 0xA66F, 0x67FF, 0x3F8A, 0x2F99, 0xE2C2, 0x656B, 0x5F31, 0x5BA6, 0xCA29,
+            0xC224, 0xB85C, 0x097C,
+        ],
+    ];
+    const KECCAK_F800: [[u64; 25]; 2] = [
+        [
+            0xE531D45D, 0xF404C6FB, 0x23A0BF99, 0xF1F8452F, 0x51FFD042, 0xE539F578, 0xF00B80A7,
+            0xAF973664, 0xBF5AF34C, 0x227A2424, 0x88172715, 0x9F685884, 0xB15CD054, 0x1BF4FC0E,
+            0x6166FA91, 0x1A9E599A, 0xA3970A1F, 0xAB659687, 0xAFAB8D68, 0xE74B1015, 0x34001A98,
+            0x4119EFF3, 0x930A0E76, 0x87B28070, 0x11EFE996,
+        ],
+        [
+            0x75BF2D0D, 0x9B610E89, 0xC826AF40, 0x64CD84AB, 0xF905BDD6, 0xBC832835, 0x5F8001B9,
+            0x15662CCE, 0x8E38C95E, 0x701FE543, 0x1B544380, 0x89ACDEFF, 0x51EDB5DE, 0x0E9702D9,
+            0x6C19AA16, 0xA2913EEE, 0x60754E9A, 0x9819063C, 0xF4709254, 0xD09F9084, 0x772DA259,
+            0x1DB35DF7, 0x5AA60162, 0x358825D5, 0xB3783BAB,
+        ],
+    ];
+
+    /// Apply Keccak-f[b] twice to the all-zero state and check all lanes after each permutation.
+    fn check_keccak_f<L: Lane>(expected: [[u64; 25]; 2]) {
+        let mut state = State::<L>::keccak_f();
+        state.permute();
+        assert_eq!(lanes(&state), expected[0]);
+        state.permute();
+        assert_eq!(lanes(&state), expected[1]);
+    }
+
+    #[test]
+    fn test_keccak_f_widths() {
+        check_keccak_f::<u8>(KECCAK_F200);
+        check_keccak_f::<u16>(KECCAK_F400);
+        check_keccak_f::<u32>(KECCAK_F800);
+    }
+
+    /// The rc function of algorithm 5 of FIPS 202, which computes one bit of a round constant with a linear feedback shift register.
+    fn reference_rc(t: usize) -> bool {
+        let mut r = [true, false, false, false, false, false, false, false];
+        for _ in 0..t % 255 {
+            // Step 3 a-f: shift a 0 in at the front and XOR the bit which falls off the end into bits 0, 4, 5 and 6.
+            r.rotate_right(1);
+            let last = r[0];
+            r[4] ^= last;
+            r[5] ^= last;
+            r[6] ^= last;
+        }
+        r[0]
+    }
+
+    /// Keccak-p[25w, rounds] computed bit by bit with algorithm 1 to 7 of FIPS 202, without the lane types, precalculated offsets and round constants of State. The Keccak team only publishes known answers for the widths from 200 bits, so this is used to check the smaller widths, and it is itself checked against the known answers for the larger widths.
+    #[allow(clippy::needless_range_loop)]
+    fn reference_keccak_p(w: usize, rounds: usize, lanes: &[u64]) -> Vec<u64> {
+        let l = w.trailing_zeros() as usize;
+        let mut a = vec![vec![vec![false; w]; 5]; 5];
+        for i in 0..25 {
+            for z in 0..w {
+                a[i % 5][i / 5][z] = (lanes[i] >> z) & 1 == 1;
+            }
+        }
+
+        for ir in 12 + 2 * l - rounds..12 + 2 * l {
+            // Algorithm 1, theta.
+            let mut c = vec![vec![false; w]; 5];
+            for x in 0..5 {
+                for z in 0..w {
+                    c[x][z] = a[x][0][z] ^ a[x][1][z] ^ a[x][2][z] ^ a[x][3][z] ^ a[x][4][z];
+                }
+            }
+            for x in 0..5 {
+                for y in 0..5 {
+                    for z in 0..w {
+                        a[x][y][z] ^= c[(x + 4) % 5][z] ^ c[(x + 1) % 5][(z + w - 1) % w];
+                    }
+                }
+            }
+
+            // Algorithm 2, rho.
+            let mut b = a.clone();
+            let (mut x, mut y) = (1, 0);
+            for t in 0..24 {
+                for z in 0..w {
+                    b[x][y][z] = a[x][y][(z + w - (t + 1) * (t + 2) / 2 % w) % w];
+                }
+                (x, y) = (y, (2 * x + 3 * y) % 5);
+            }
+
+            // Algorithm 3, pi.
+            for x in 0..5 {
+                for y in 0..5 {
+                    a[x][y] = b[(x + 3 * y) % 5][x].clone();
+                }
+            }
+
+            // Algorithm 4, chi.
+            let b = a.clone();
+            for x in 0..5 {
+                for y in 0..5 {
+                    for z in 0..w {
+                        a[x][y][z] = b[x][y][z] ^ (!b[(x + 1) % 5][y][z] & b[(x + 2) % 5][y][z]);
+                    }
+                }
+            }
+
+            // Algorithm 6, iota.
+            for j in 0..=l {
+                a[0][0][(1 << j) - 1] ^= reference_rc(j + 7 * ir);
+            }
+        }
+
+        (0..25)
+            .map(|i| (0..w).map(|z| (a[i % 5][i / 5][z] as u64) << z).sum())
+            .collect()
+    }
+
+    /// Check the permutation of State against the reference permutation for the all-zero state and a few other states, with all rounds and with fewer rounds.
+    fn check_reference<L: Lane>() {
+        let w = L::BITS as usize;
+        let mask = u64::MAX >> (64 - w);
+
+        for seed in 0..4u64 {
+            let input: Vec<u64> = (0..25)
+                .map(|i| seed.wrapping_mul(0x9E3779B97F4A7C15).rotate_left(i * 7) & mask)
+                .collect();
+
+            for rounds in [State::<L>::MAX_ROUNDS, 4, 1] {
+                let mut state = State::<L>::keccak_p(rounds);
+                for (i, lane) in input.iter().enumerate() {
+                    state.set_lane(i % 5, i / 5, L::from_u64(*lane));
+                }
+                state.permute();
+                assert_eq!(lanes(&state), reference_keccak_p(w, rounds, &input));
+            }
+        }
+    }
+
+    #[test]
+    fn test_reference_keccak_p() {
+        // The reference permutation gives the known answers of the Keccak team.
+        for (w, rounds, expected) in [
+            (8, 18, KECCAK_F200),
+            (16, 20, KECCAK_F400),
+            (32, 22, KECCAK_F800),
+        ] {
+            let first = reference_keccak_p(w, rounds, &[0; 25]);
+            assert_eq!(first, expected[0]);
+            assert_eq!(reference_keccak_p(w, rounds, &first), expected[1]);
+        }
+        assert_eq!(
+            reference_keccak_p(64, 24, &[0; 25])[..4],
+            [
+                0xF1258F7940E1DDE7,
+                0x84D5CCF933C0478A,
+                0xD598261EA65AA9EE,
+                0xBD1547306F80494D
+            ]
+        );
+
+        // Keccak-f[25], Keccak-f[50] and Keccak-f[100] are checked against the reference, together with the larger widths.
+        check_reference::<SmallLane<1>>();
+        check_reference::<SmallLane<2>>();
+        check_reference::<SmallLane<4>>();
+        check_reference::<u8>();
+        check_reference::<u16>();
+        check_reference::<u32>();
+        check_reference::<u64>();
+    }
+
+    #[test]
+    fn test_widths() {
+        assert_eq!((State::<u64>::WIDTH, State::<u64>::MAX_ROUNDS), (1600, 24));
+        assert_eq!((State::<u32>::WIDTH, State::<u32>::MAX_ROUNDS), (800, 22));
+        assert_eq!((State::<u8>::WIDTH, State::<u8>::MAX_ROUNDS), (200, 18));
+        assert_eq!(
+            (
+                State::<SmallLane<1>>::WIDTH,
+                State::<SmallLane<1>>::MAX_ROUNDS
+            ),
+            (25, 12)
+        );
+
+        // Keccak-p[200, 4] is the last 4 rounds of Keccak-f[200], known answer computed with keccak_p of the keccak crate of RustCrypto.
+        let mut state = State::<u8>::keccak_p(4);
+        state.permute();
+        assert_eq!(
+            lanes(&state),
+            [
+                0xB4, 0x81, 0xA6, 0x04, 0xEB, 0x38, 0x9A, 0x73, 0x4E, 0xD8, 0x7C, 0x0D, 0x40, 0xF3,
+                0x44, 0x8B, 0xE3, 0x3E, 0x9B, 0x60, 0xF2, 0x32, 0x49, 0xCA, 0xA2,
+            ]
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "Invalid number of rounds")]
+    fn test_too_many_rounds() {
+        State::<u16>::keccak_p(21);
+    }
+
+    #[test]
+    fn test_small_bytes() {
+        // Keccak-f[25] has 25 bits, so the last of the 4 bytes only has 1 bit which is in lane 24.
+        let mut state = State::<SmallLane<1>>::keccak_f();
+        state.xor_bytes(0, &[0x81, 0x00, 0x00, 0x01]);
+        assert_eq!(state.lane(0, 0).value(), 1);
+        assert_eq!(state.lane(2, 1).value(), 1);
+        assert_eq!(state.lane(4, 4).value(), 1);
+
+        let mut buffer = [0xFF; 4];
+        state.extract_bytes(0, &mut buffer);
+        assert_eq!(buffer, [0x81, 0x00, 0x00, 0x01]);
+
+        // Lanes of 4 bits hold 2 lanes in a byte.
+        let mut state = State::<SmallLane<4>>::keccak_f();
+        state.xor_bytes(1, &[0xA5]);
+        assert_eq!(state.lane(2, 0).value(), 0x5);
+        assert_eq!(state.lane(3, 0).value(), 0xA);
+    }
+
+    #[test]
+    #[should_panic(expected = "Too many bits for the state")]
+    fn test_small_bytes_past_end() {
+        State::<SmallLane<1>>::keccak_f().xor_bytes(3, &[0x02]);
+    }
+
+    #[test]
+    fn test_rounds() {
+        let mut full = State::new();
+        let mut reduced = State::with_rounds(12);
+        assert_eq!(reduced.rounds(), 12);
+
+        full.permute();
+        reduced.permute();
+        assert_ne!(full.lane(0, 0), reduced.lane(0, 0));
+
+        // With 0 rounds, the permutation does nothing.
+        let mut none = State::with_rounds(0);
+        none.set_lane(1, 2, 42);
+        none.permute();
+        assert_eq!(none.lane(1, 2), 42);
+    }
+
+    #[test]
+    fn test_bytes() {
+        let mut state = State::new();
+        state.xor_bytes(0, &[0x01, 0x02, 0x03, 0x04, 0x05, 0x06, 0x07, 0x08, 0xFF]);
+        assert_eq!(state.lane(0, 0), 0x0807060504030201);
+        assert_eq!(state.lane(1, 0), 0xFF);
+
+        // Lane 5 is the first lane of the second row, at byte 40.
+        state.xor_bytes(41, &[0xAB]);
+        assert_eq!(state.lane(0, 1), 0xAB00);
+
+        let mut buffer = [0; 3];
+        state.extract_bytes(7, &mut buffer);
+        assert_eq!(buffer, [0x08, 0xFF, 0x00]);
+
+        // XOR the same bytes again to clear them.
+        state.xor_bytes(41, &[0xAB]);
+        assert_eq!(state.lane(0, 1), 0);
+
+        let mut all = [0; 200];
+        state.extract_bytes(0, &mut all);
+        assert_eq!(
+            all[..9],
+            [0x01, 0x02, 0x03, 0x04, 0x05, 0x06, 0x07, 0x08, 0xFF]
+        );
+    }
+
+    #[test]
+    fn test_absorb_matches_xor_bytes() {
+        let block: Vec<u8> = (0..136).collect();
+
+        let mut a = State::new();
+        a.absorb(&block);
+
+        let mut b = State::new();
+        b.xor_bytes(0, &block);
+        b.permute();
+
+        let mut out = [0; 200];
+        b.extract_bytes(0, &mut out);
+        assert_eq!(a.output_block(1600), out.to_vec());
     }
 }
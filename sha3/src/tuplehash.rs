@@ -0,0 +1,203 @@
+use crate::cshake::CShakeHasher;
+use crate::error::Error;
+use crate::input::Input;
+use crate::shake::check_output_length;
+use crate::utils::{bytes_to_hex, encode_string, right_encode};
+
+/// The TupleHash function as defined in section 5 of SP 800-185, which hashes a tuple of strings so that the boundaries between them are part of the hash. This means that for example ("ab", "c") and ("a", "bc") give different outputs. The capacity has to be 128 or 256, the size is the final length of the output in bits (has to be a number divisible by 8) and the customization string can be used to get a different function for each use. Panics if the capacity or size is invalid.
+pub fn tuplehash<T: Input, S: Input>(
+    capacity: usize,
+    tuple: &[T],
+    size: usize,
+    customization: S,
+) -> String {
+    bytes_to_hex(&tuplehash_bytes(capacity, tuple, size, customization))
+}
+
+/// The TupleHash function returning the raw bytes of the output instead of a hex string. The parameters are the same as for the tuplehash function.
+pub fn tuplehash_bytes<T: Input, S: Input>(
+    capacity: usize,
+    tuple: &[T],
+    size: usize,
+    customization: S,
+) -> Vec<u8> {
+    try_tuplehash_bytes(capacity, tuple, size, customization).unwrap_or_else(|e| panic!("{}", e))
+}
+
+/// Fallible version of the tuplehash_bytes function, which returns an error instead of panicking when the capacity or size is invalid.
+pub fn try_tuplehash_bytes<T: Input, S: Input>(
+    capacity: usize,
+    tuple: &[T],
+    size: usize,
+    customization: S,
+) -> Result<Vec<u8>, Error> {
+    check_output_length(size)?;
+
+    let mut hasher = absorb_tuple(capacity, tuple, customization)?;
+    hasher.update(right_encode(size));
+    hasher.try_finalize_bytes(size)
+}
+
+/// Shortcut for the TupleHash128 function, the parameters are the same as for the tuplehash function.
+pub fn tuplehash128<T: Input, S: Input>(tuple: &[T], size: usize, customization: S) -> String {
+    tuplehash(128, tuple, size, customization)
+}
+
+/// Shortcut for the TupleHash256 function, the parameters are the same as for the tuplehash function.
+pub fn tuplehash256<T: Input, S: Input>(tuple: &[T], size: usize, customization: S) -> String {
+    tuplehash(256, tuple, size, customization)
+}
+
+/// The TupleHashXOF function, which is TupleHash where the output length is not part of the input. The parameters are the same as for the tuplehash function, but here the size is just the number of bits returned (has to be a number divisible by 8). Panics if the capacity or size is invalid.
+pub fn tuplehashxof<T: Input, S: Input>(
+    capacity: usize,
+    tuple: &[T],
+    size: usize,
+    customization: S,
+) -> String {
+    bytes_to_hex(&tuplehashxof_bytes(capacity, tuple, size, customization))
+}
+
+/// The TupleHashXOF function returning the raw bytes of the output instead of a hex string. The parameters are the same as for the tuplehashxof function.
+pub fn tuplehashxof_bytes<T: Input, S: Input>(
+    capacity: usize,
+    tuple: &[T],
+    size: usize,
+    customization: S,
+) -> Vec<u8> {
+    try_tuplehashxof_bytes(capacity, tuple, size, customization).unwrap_or_else(|e| panic!("{}", e))
+}
+
+/// Fallible version of the tuplehashxof_bytes function, which returns an error instead of panicking when the capacity or size is invalid.
+pub fn try_tuplehashxof_bytes<T: Input, S: Input>(
+    capacity: usize,
+    tuple: &[T],
+    size: usize,
+    customization: S,
+) -> Result<Vec<u8>, Error> {
+    check_output_length(size)?;
+
+    let mut hasher = absorb_tuple(capacity, tuple, customization)?;
+    // The XOF variants use an output length of 0, since it is not known in advance.
+    hasher.update(right_encode(0));
+
+    let mut out = vec![0; size / 8];
+    hasher.finalize_xof().squeeze(&mut out);
+    Ok(out)
+}
+
+/// The TupleHashXOF128 function, which is TupleHash128 where the output length is not part of the input. Here, the size is just the number of bits returned (has to be a number divisible by 8).
+pub fn tuplehashxof128<T: Input, S: Input>(tuple: &[T], size: usize, customization: S) -> String {
+    tuplehashxof(128, tuple, size, customization)
+}
+
+/// The TupleHashXOF256 function, which is TupleHash256 where the output length is not part of the input. Here, the size is just the number of bits returned (has to be a number divisible by 8).
+pub fn tuplehashxof256<T: Input, S: Input>(tuple: &[T], size: usize, customization: S) -> String {
+    tuplehashxof(256, tuple, size, customization)
+}
+
+/// Absorb every element of the tuple as encode_string(X[i]) into cSHAKE with the function name "TupleHash", which is used by both TupleHash and TupleHashXOF. The length of each element is encoded in front of it, which is what makes the boundaries unambiguous.
+fn absorb_tuple<T: Input, S: Input>(
+    capacity: usize,
+    tuple: &[T],
+    customization: S,
+) -> Result<CShakeHasher, Error> {
+    let mut hasher = CShakeHasher::try_new(capacity, "TupleHash", customization)?;
+
+    for element in tuple {
+        hasher.update(encode_string(element.convert()));
+    }
+
+    Ok(hasher)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const X: [&[u8]; 3] = [
+        &[0x00, 0x01, 0x02],
+        &[0x10, 0x11, 0x12, 0x13, 0x14, 0x15],
+        &[0x20, 0x21, 0x22, 0x23, 0x24, 0x25, 0x26, 0x27, 0x28],
+    ];
+
+    #[test]
+    fn test_tuplehash128() {
+        // Sample #1, #2 and #3 from the TupleHash samples of NIST.
+        assert_eq!(
+            tuplehash128(&X[..2], 256, ""),
+            "c5d8786c1afb9b82111ab34b65b2c0048fa64e6d48e263264ce1707d3ffc8ed1"
+        );
+        assert_eq!(
+            tuplehash128(&X[..2], 256, "My Tuple App"),
+            "75cdb20ff4db1154e841d758e24160c54bae86eb8c13e7f5f40eb35588e96dfb"
+        );
+        assert_eq!(
+            tuplehash128(&X, 256, "My Tuple App"),
+            "e60f202c89a2631eda8d4c588ca5fd07f39e5151998deccf973adb3804bb6e84"
+        );
+    }
+
+    #[test]
+    fn test_tuplehash256() {
+        // Sample #4 from the TupleHash samples of NIST.
+        assert_eq!(
+            tuplehash256(&X[..2], 512, ""),
+            "cfb7058caca5e668f81a12a20a2195ce97a925f1dba3e7449a56f82201ec607311ac2696b1ab5ea2352df1423bde7bd4bb78c9aed1a853c78672f9eb23bbe194"
+        );
+    }
+
+    #[test]
+    fn test_tuplehashxof() {
+        // Sample #1 from the TupleHashXOF samples of NIST.
+        assert_eq!(
+            tuplehashxof128(&X[..2], 256, ""),
+            "2f103cd7c32320353495c68de1a8129245c6325f6f2a3d608d92179c96e68488"
+        );
+        assert_ne!(
+            tuplehashxof256(&X[..2], 512, ""),
+            tuplehash256(&X[..2], 512, "")
+        );
+    }
+
+    #[test]
+    fn test_tuplehash_boundaries() {
+        assert_ne!(
+            tuplehash128(&["ab", "c"], 256, ""),
+            tuplehash128(&["a", "bc"], 256, "")
+        );
+        assert_ne!(
+            tuplehash128(&["abc"], 256, ""),
+            tuplehash128(&["abc", ""], 256, "")
+        );
+    }
+
+    #[test]
+    fn test_try_tuplehash() {
+        assert_eq!(
+            try_tuplehash_bytes(100, &X, 256, ""),
+            Err(Error::InvalidCapacity(100))
+        );
+        assert_eq!(
+            try_tuplehash_bytes(256, &X, 4, ""),
+            Err(Error::InvalidOutputLength(4))
+        );
+    }
+
+    #[test]
+    fn test_try_tuplehashxof() {
+        // Sample #4 from the TupleHashXOF samples of NIST.
+        assert_eq!(
+            try_tuplehashxof_bytes(256, &X[..2], 512, "").map(|out| bytes_to_hex(&out)),
+            Ok("03ded4610ed6450a1e3f8bc44951d14fbc384ab0efe57b000df6b6df5aae7cd568e77377daf13f37ec75cf5fc598b6841d51dd207c991cd45d210ba60ac52eb9".to_string())
+        );
+        assert_eq!(
+            try_tuplehashxof_bytes(512, &X[..2], 256, ""),
+            Err(Error::InvalidCapacity(512))
+        );
+        assert_eq!(
+            try_tuplehashxof_bytes(128, &X[..2], 12, ""),
+            Err(Error::InvalidOutputLength(12))
+        );
+    }
+}
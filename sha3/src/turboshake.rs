@@ -0,0 +1,195 @@
+use crate::error::Error;
+use crate::input::Input;
+use crate::shake::{check_capacity, check_output_length};
+use crate::sponge::{Sponge, XofReader};
+use crate::utils::bytes_to_hex;
+
+/// The TurboSHAKE function as defined in RFC 9861, which is SHAKE with the permutation reduced to 12 rounds (Keccak-p[1600, 12]) for about twice the speed. The capacity has to be 128 or 256, the size is the final length of the output in bits (has to be a number divisible by 8) and the domain separation byte has to be between 0x01 and 0x7F (0x1F is the default). Panics if the capacity, domain or size is invalid.
+pub fn turboshake<T: Input>(capacity: usize, input: T, size: usize, domain: u8) -> String {
+    bytes_to_hex(&turboshake_bytes(capacity, input, size, domain))
+}
+
+/// The TurboSHAKE function returning the raw bytes of the output instead of a hex string. The parameters are the same as for the turboshake function.
+pub fn turboshake_bytes<T: Input>(capacity: usize, input: T, size: usize, domain: u8) -> Vec<u8> {
+    try_turboshake_bytes(capacity, input, size, domain).unwrap_or_else(|e| panic!("{}", e))
+}
+
+/// Fallible version of the turboshake_bytes function, which returns an error instead of panicking when the capacity, domain or size is invalid.
+pub fn try_turboshake_bytes<T: Input>(
+    capacity: usize,
+    input: T,
+    size: usize,
+    domain: u8,
+) -> Result<Vec<u8>, Error> {
+    let mut hasher = TurboShakeHasher::try_new(capacity, domain)?;
+    hasher.update(input);
+    hasher.try_finalize_bytes(size)
+}
+
+/// Shortcut for the TurboSHAKE128 function, the parameters are the same as for the turboshake function.
+pub fn turboshake128<T: Input>(input: T, size: usize, domain: u8) -> String {
+    turboshake(128, input, size, domain)
+}
+
+/// Shortcut for the TurboSHAKE256 function, the parameters are the same as for the turboshake function.
+pub fn turboshake256<T: Input>(input: T, size: usize, domain: u8) -> String {
+    turboshake(256, input, size, domain)
+}
+
+/// Check that the domain separation byte is between 0x01 and 0x7F, so it contains the first padding bit and leaves room for the last one.
+pub fn check_domain(domain: u8) -> Result<(), Error> {
+    match domain {
+        0x01..=0x7F => Ok(()),
+        _ => Err(Error::InvalidDomain(domain)),
+    }
+}
+
+/// Incremental version of the TurboSHAKE function for input which is not available all at once. It works like the ShakeHasher, but with a caller chosen domain separation byte.
+#[derive(Clone)]
+pub struct TurboShakeHasher {
+    sponge: Sponge,
+    domain: u8,
+}
+
+impl TurboShakeHasher {
+    /// Create a new hasher with the given capacity (128 or 256) and domain separation byte (between 0x01 and 0x7F). Panics if the capacity or domain is invalid.
+    pub fn new(capacity: usize, domain: u8) -> TurboShakeHasher {
+        TurboShakeHasher::try_new(capacity, domain).unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    /// Fallible version of new, which returns an error instead of panicking when the capacity or domain is invalid.
+    pub fn try_new(capacity: usize, domain: u8) -> Result<TurboShakeHasher, Error> {
+        check_capacity(capacity)?;
+        check_domain(domain)?;
+
+        Ok(TurboShakeHasher {
+            sponge: Sponge::with_rounds(capacity * 2, 12),
+            domain,
+        })
+    }
+
+    /// Add more input to the hasher, the input can be of any type that implements the Input trait.
+    pub fn update<T: Input>(&mut self, input: T) {
+        self.sponge.update(input.convert());
+    }
+
+    /// Consume the hasher and return the output with the given size in bits (has to be a number divisible by 8).
+    pub fn finalize(self, size: usize) -> String {
+        bytes_to_hex(&self.finalize_bytes(size))
+    }
+
+    /// Consume the hasher and return the raw bytes of the output with the given size in bits (has to be a number divisible by 8).
+    pub fn finalize_bytes(self, size: usize) -> Vec<u8> {
+        self.try_finalize_bytes(size)
+            .unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    /// Fallible version of finalize_bytes, which returns an error instead of panicking when the size is invalid.
+    pub fn try_finalize_bytes(self, size: usize) -> Result<Vec<u8>, Error> {
+        check_output_length(size)?;
+
+        // The domain byte works like the domain separator of SHAKE, since its most significant set bit is the first padding bit.
+        Ok(self.sponge.finalize(self.domain, size))
+    }
+
+    /// Consume the hasher and return a reader from which any amount of output can be read.
+    pub fn finalize_xof(self) -> XofReader {
+        self.sponge.finalize_xof(self.domain)
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+
+    /// The pattern used for the messages of the test vectors in RFC 9861, which repeats the bytes from 0x00 to 0xFA.
+    pub(crate) fn ptn(n: usize) -> Vec<u8> {
+        (0..n).map(|i| (i % 251) as u8).collect()
+    }
+
+    #[test]
+    fn test_turboshake128() {
+        // Test vectors from section 5 of RFC 9861.
+        assert_eq!(
+            turboshake128("", 256, 0x1F),
+            "1e415f1c5983aff2169217277d17bb538cd945a397ddec541f1ce41af2c1b74c"
+        );
+        assert_eq!(
+            turboshake128("", 512, 0x1F),
+            "1e415f1c5983aff2169217277d17bb538cd945a397ddec541f1ce41af2c1b74c3e8ccae2a4dae56c84a04c2385c03c15e8193bdf58737363321691c05462c8df"
+        );
+        assert_eq!(
+            turboshake128(ptn(17), 256, 0x1F),
+            "9c97d036a3bac819db70ede0ca554ec6e4c2a1a4ffbfd9ec269ca6a111161233"
+        );
+        assert_eq!(
+            turboshake128(ptn(17 * 17), 256, 0x1F),
+            "96c77c279e0126f7fc07c9b07f5cdae1e0be60bdbe10620040e75d7223a624d2"
+        );
+    }
+
+    #[test]
+    fn test_turboshake256() {
+        // Test vectors from section 5 of RFC 9861.
+        assert_eq!(
+            turboshake256("", 512, 0x1F),
+            "367a329dafea871c7802ec67f905ae13c57695dc2c6663c61035f59a18f8e7db11edc0e12e91ea60eb6b32df06dd7f002fbafabb6e13ec1cc20d995547600db0"
+        );
+    }
+
+    #[test]
+    fn test_turboshake_domain() {
+        assert_ne!(turboshake128("", 256, 0x1F), turboshake128("", 256, 0x07));
+        assert_ne!(
+            turboshake128("", 256, 0x1F),
+            crate::shake::shake128("", 256)
+        );
+
+        // Test vectors from section 5 of RFC 9861 with other domain separation bytes, where the message is 1, 3 or 7 bytes of 0xFF.
+        assert_eq!(
+            turboshake128([0xFF; 3].as_slice(), 256, 0x07),
+            "b658576001cad9b1e5f399a9f77723bba05458042d68206f7252682dba3663ed"
+        );
+        assert_eq!(
+            turboshake128([0xFF; 7].as_slice(), 256, 0x0B),
+            "8deeaa1aec47ccee569f659c21dfa8e112db3cee37b18178b2acd805b799cc37"
+        );
+        assert_eq!(
+            turboshake128([0xFF].as_slice(), 256, 0x30),
+            "553122e2135e363c3292bed2c6421fa232bab03daa07c7d6636603286506325b"
+        );
+
+        // The largest domain byte has its first padding bit in the last bit of the byte.
+        assert_eq!(
+            turboshake128([0xFF; 3].as_slice(), 256, 0x7F),
+            "16274cc656d44cefd422395d0f9053bda6d28e122aba15c765e5ad0e6eaf26f9"
+        );
+        assert_eq!(
+            turboshake256([0xFF; 3].as_slice(), 512, 0x7F),
+            "abe569c1f77ec340f02705e7d37c9ab7e155516e4a6a150021d70b6fac0bb40c069f9a9828a0d575cd99f9bae435ab1acf7ed9110ba97ce0388d074bac768776"
+        );
+
+        assert_eq!(
+            try_turboshake_bytes(128, "", 256, 0x00),
+            Err(Error::InvalidDomain(0x00))
+        );
+        assert_eq!(
+            try_turboshake_bytes(128, "", 256, 0x80),
+            Err(Error::InvalidDomain(0x80))
+        );
+    }
+
+    #[test]
+    fn test_turboshake_hasher() {
+        let input = ptn(1000);
+
+        let mut hasher = TurboShakeHasher::new(128, 0x0B);
+        for chunk in input.chunks(100) {
+            hasher.update(chunk);
+        }
+
+        let mut out = [0; 64];
+        hasher.finalize_xof().squeeze(&mut out);
+        assert_eq!(bytes_to_hex(&out), turboshake128(input, 512, 0x0B));
+    }
+}
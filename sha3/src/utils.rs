@@ -1,7 +1,8 @@
+use crate::error::Error;
 use crate::state::State;
 
 /// Implementation of the keccak function to be used for SHA-3 and SHAKE with the capacity, output size and input vector as input. The capacity is used to determine the block size.
-pub fn keccak(capacity: usize, size: usize, input: Vec<u8>) -> String {
+pub fn keccak(capacity: usize, size: usize, input: Vec<u8>) -> Vec<u8> {
     // Rate is defined as 1600 - capacity in the keccak algorithm used by SHA-3 and SHAKE.
     let r = 1600 - capacity;
 
@@ -15,18 +16,23 @@ pub fn keccak(capacity: usize, size: usize, input: Vec<u8>) -> String {
 
     // For each of the blocks, we "absorb" the block into the state and do the keccak permutations.
     for block in p.chunks(block_size) {
-        state.absorb(block.to_vec());
+        state.absorb(block);
     }
 
-    bytes_to_hex(state.squeeze(r, size))
+    state.squeeze(r, size)
 }
 
 /// Padding according to appendix B.2 of FIPS 202. The domain separator is added in the SHA-3 and SHAKE functions.
-fn pad10star1(m: Vec<u8>, block_size: usize) -> Vec<u8> {
+pub fn pad10star1(m: Vec<u8>, block_size: usize) -> Vec<u8> {
     let mut p = m;
 
+    // The first padding bit is the most significant set bit of the last byte. If it is the last bit of the byte (which can happen for messages that are not a whole number of bytes), the final padding bit has to go in a new byte.
+    if p.last().is_some_and(|byte| byte & 0x80 != 0) {
+        p.push(0x00);
+    }
+
     // The domain separator is added, so we just start adding 0-bytes until the length is a divisor of the block size. If it already is, nothing happens.
-    while p.len() % block_size != 0 {
+    while !p.len().is_multiple_of(block_size) {
         p.push(0x00);
     }
 
@@ -37,8 +43,97 @@ fn pad10star1(m: Vec<u8>, block_size: usize) -> Vec<u8> {
     p
 }
 
-/// Simple method of getting a hex string from a vec of bytes
-fn bytes_to_hex(bytes: Vec<u8>) -> String {
+/// Append the domain separator (the suffix bits followed by the first padding bit) to a message which is the first "bits" bits of the input. The bits are numbered like in FIPS 202, so bit i of the message is bit i mod 8 of byte i / 8, counting from the least significant bit.
+pub fn append_suffix(input: &[u8], bits: usize, suffix: u8) -> Vec<u8> {
+    let mut m = input[..bits.div_ceil(8)].to_vec();
+
+    let used = bits % 8;
+    if used == 0 {
+        m.push(suffix);
+        return m;
+    }
+
+    // Clear the unused bits of the last byte and place the suffix right after the message bits, which may spill over into a new byte.
+    let last = m.pop().unwrap() & ((1 << used) - 1);
+    let merged = last as u16 | (suffix as u16) << used;
+    m.push(merged as u8);
+    if merged > 0xFF {
+        m.push((merged >> 8) as u8);
+    }
+
+    m
+}
+
+/// Check that the message length in bits fits in the input.
+pub fn check_bit_length(input: &[u8], bits: usize) -> Result<(), Error> {
+    if bits > input.len() * 8 {
+        return Err(Error::InvalidBitLength(bits));
+    }
+
+    Ok(())
+}
+
+/// The left_encode function as defined in section 2.3.1 of SP 800-185. The integer is encoded as big endian bytes with the number of bytes in front.
+pub fn left_encode(x: usize) -> Vec<u8> {
+    let mut out = integer_bytes(x);
+    out.insert(0, out.len() as u8);
+    out
+}
+
+/// The right_encode function as defined in section 2.3.1 of SP 800-185. The integer is encoded as big endian bytes with the number of bytes after it.
+pub fn right_encode(x: usize) -> Vec<u8> {
+    let mut out = integer_bytes(x);
+    out.push(out.len() as u8);
+    out
+}
+
+/// The big endian bytes of an integer without leading 0-bytes, but always at least one byte like required by left_encode and right_encode.
+fn integer_bytes(x: usize) -> Vec<u8> {
+    let bytes = x.to_be_bytes();
+    let first = bytes
+        .iter()
+        .position(|&byte| byte != 0)
+        .unwrap_or(bytes.len() - 1);
+    bytes[first..].to_vec()
+}
+
+/// The encode_string function as defined in section 2.3.2 of SP 800-185, which prefixes a string with its length in bits so it can be parsed unambiguously.
+pub fn encode_string(s: &[u8]) -> Vec<u8> {
+    let mut out = left_encode(s.len() * 8);
+    out.extend_from_slice(s);
+    out
+}
+
+/// The bytepad function as defined in section 2.3.3 of SP 800-185, which prefixes the input with the encoding of w and pads it with 0-bytes until the length is a multiple of w.
+pub fn bytepad(x: Vec<u8>, w: usize) -> Vec<u8> {
+    let mut z = left_encode(w);
+    z.extend(x);
+
+    while !z.len().is_multiple_of(w) {
+        z.push(0x00);
+    }
+
+    z
+}
+
+/// Compare two byte strings in constant time, so the time taken does not reveal how many bytes of a secret value like a tag were guessed correctly. Only the length is allowed to leak.
+pub fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    if a.len() != b.len() {
+        return false;
+    }
+
+    // OR together the differences of all bytes instead of stopping at the first one.
+    let mut diff = 0;
+    for (x, y) in a.iter().zip(b) {
+        diff |= x ^ y;
+    }
+
+    // black_box keeps the compiler from turning the loop into an early return.
+    std::hint::black_box(diff) == 0
+}
+
+/// Simple method of getting a hex string from a slice of bytes, used to present the output of the hashing algorithms.
+pub fn bytes_to_hex(bytes: &[u8]) -> String {
     let mut s = String::new();
 
     for byte in bytes {
@@ -49,6 +144,20 @@ fn bytes_to_hex(bytes: Vec<u8>) -> String {
     s
 }
 
+/// Parse a hex string into bytes, which is the opposite of bytes_to_hex. Both lowercase and uppercase digits are accepted, and an empty string gives no bytes. Returns an error if the string has an odd length or a character which is not a hex digit.
+pub fn hex_to_bytes(hex: &str) -> Result<Vec<u8>, Error> {
+    // Check the digits first, since u8::from_str_radix also accepts a "+" sign.
+    if !hex.len().is_multiple_of(2) || !hex.bytes().all(|byte| byte.is_ascii_hexdigit()) {
+        return Err(Error::InvalidHex(hex.to_string()));
+    }
+
+    // Take two digits at a time, since each byte is two hex digits.
+    Ok((0..hex.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(&hex[i..i + 2], 16).unwrap())
+        .collect())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -62,6 +171,67 @@ mod tests {
             0x00, 0x80,
         ];
         assert_eq!(pad10star1(m, block_size), expected_output);
+
+        // The first padding bit is the last bit of the block, so the final padding bit needs an extra block.
+        let m = vec![0x00, 0x01, 0x02, 0xC0];
+        let block_size = 4;
+        let expected_output = vec![0x00, 0x01, 0x02, 0xC0, 0x00, 0x00, 0x00, 0x80];
+        assert_eq!(pad10star1(m, block_size), expected_output);
+    }
+
+    #[test]
+    fn test_append_suffix() {
+        // Whole bytes, the suffix is just added as a new byte.
+        assert_eq!(
+            append_suffix(&[0xAB, 0xCD], 16, 0x06),
+            vec![0xAB, 0xCD, 0x06]
+        );
+
+        // The unused bits of the last byte are cleared before adding the suffix.
+        assert_eq!(append_suffix(&[0xFF], 5, 0x06), vec![0xDF]);
+        assert_eq!(append_suffix(&[0xAB, 0xFF], 9, 0x06), vec![0xAB, 0x0D]);
+
+        // The suffix spills over into a new byte.
+        assert_eq!(append_suffix(&[0x13], 5, 0x1F), vec![0xF3, 0x03]);
+        assert_eq!(append_suffix(&[0x13, 0xFF], 5, 0x06), vec![0xD3]);
+    }
+
+    #[test]
+    fn test_left_encode() {
+        assert_eq!(left_encode(0), vec![0x01, 0x00]);
+        assert_eq!(left_encode(168), vec![0x01, 0xA8]);
+        assert_eq!(left_encode(256), vec![0x02, 0x01, 0x00]);
+        assert_eq!(left_encode(0x010000), vec![0x03, 0x01, 0x00, 0x00]);
+    }
+
+    #[test]
+    fn test_right_encode() {
+        assert_eq!(right_encode(0), vec![0x00, 0x01]);
+        assert_eq!(right_encode(256), vec![0x01, 0x00, 0x02]);
+    }
+
+    #[test]
+    fn test_encode_string() {
+        assert_eq!(encode_string(b""), vec![0x01, 0x00]);
+        assert_eq!(encode_string(b"abc"), vec![0x01, 0x18, 0x61, 0x62, 0x63]);
+    }
+
+    #[test]
+    fn test_bytepad() {
+        assert_eq!(
+            bytepad(vec![0x01, 0x02], 8),
+            vec![0x01, 0x08, 0x01, 0x02, 0x00, 0x00, 0x00, 0x00]
+        );
+        assert_eq!(bytepad(vec![0x01; 6], 8).len(), 8);
+        assert_eq!(bytepad(vec![0x01; 7], 8).len(), 16);
+    }
+
+    #[test]
+    fn test_constant_time_eq() {
+        assert!(constant_time_eq(&[], &[]));
+        assert!(constant_time_eq(&[1, 2, 3], &[1, 2, 3]));
+        assert!(!constant_time_eq(&[1, 2, 3], &[1, 2, 4]));
+        assert!(!constant_time_eq(&[1, 2, 3], &[1, 2]));
     }
 
     #[test]
@@ -69,16 +239,35 @@ mod tests {
         // Test case 1
         let bytes = vec![0x61, 0x62, 0x63]; // "abc"
         let expected_output = "616263";
-        assert_eq!(bytes_to_hex(bytes), expected_output);
+        assert_eq!(bytes_to_hex(&bytes), expected_output);
 
         // Test case 2
         let bytes = vec![0x00, 0x01, 0x02, 0x03, 0x04, 0x05]; // [0, 1, 2, 3, 4, 5]
         let expected_output = "000102030405";
-        assert_eq!(bytes_to_hex(bytes), expected_output);
+        assert_eq!(bytes_to_hex(&bytes), expected_output);
 
         // Test case 3
         let bytes = vec![0xff, 0xff, 0xff, 0xff]; // [255, 255, 255, 255]
         let expected_output = "ffffffff";
-        assert_eq!(bytes_to_hex(bytes), expected_output);
+        assert_eq!(bytes_to_hex(&bytes), expected_output);
+    }
+
+    #[test]
+    fn test_hex_to_bytes() {
+        assert_eq!(hex_to_bytes("616263"), Ok(vec![0x61, 0x62, 0x63]));
+        assert_eq!(hex_to_bytes("00FFfe"), Ok(vec![0x00, 0xFF, 0xFE]));
+        assert_eq!(hex_to_bytes(""), Ok(Vec::new()));
+        assert_eq!(
+            hex_to_bytes(&bytes_to_hex(&[0x12, 0xAB])),
+            Ok(vec![0x12, 0xAB])
+        );
+
+        assert_eq!(
+            hex_to_bytes("abc"),
+            Err(Error::InvalidHex("abc".to_string()))
+        );
+        assert_eq!(hex_to_bytes("0g"), Err(Error::InvalidHex("0g".to_string())));
+        assert_eq!(hex_to_bytes("+1"), Err(Error::InvalidHex("+1".to_string())));
+        assert_eq!(hex_to_bytes("éa"), Err(Error::InvalidHex("éa".to_string())));
     }
 }
@@ -12,8 +12,10 @@ use floem::{
     window::WindowConfig,
     Application, IntoView, View,
 };
-use sha3::{sha3, shake, Input};
-use std::fs;
+use sha3::{hash_reader, sha3, shake, Input, ShakeHasher};
+use std::fs::File;
+use std::io::{self, Read};
+use std::path::PathBuf;
 
 #[derive(Clone)]
 enum InputMethod {
@@ -72,6 +74,29 @@ fn run_algorithm<T: Input>(algorithm: Algorithm, input: T, size: usize) -> Strin
     }
 }
 
+fn run_algorithm_reader<R: Read>(
+    algorithm: Algorithm,
+    mut reader: R,
+    size: usize,
+) -> io::Result<String> {
+    match algorithm {
+        Algorithm::SHA3(variant) => match variant {
+            SHA3Variant::SHA3_224 => hash_reader(224, reader),
+            SHA3Variant::SHA3_256 => hash_reader(256, reader),
+            SHA3Variant::SHA3_384 => hash_reader(384, reader),
+            SHA3Variant::SHA3_512 => hash_reader(512, reader),
+        },
+        Algorithm::SHAKE(variant) => {
+            let mut hasher = match variant {
+                SHAKEVariant::SHAKE128 => ShakeHasher::new(128),
+                SHAKEVariant::SHAKE256 => ShakeHasher::new(256),
+            };
+            io::copy(&mut reader, &mut hasher)?;
+            Ok(hasher.finalize(size))
+        }
+    }
+}
+
 fn text_user_input(
     input_hash: RwSignal<String>,
     algorithm: RwSignal<Algorithm>,
@@ -99,14 +124,18 @@ fn file_user_input(
     size: RwSignal<usize>,
 ) -> impl IntoView {
     let selected_file = create_rw_signal(String::new());
-    let file_contents = create_rw_signal(Vec::new());
+    let file_path = create_rw_signal(None::<PathBuf>);
 
+    // The file is streamed through the hasher instead of being read into memory, so large files can be hashed as well. If the file can not be read, the error is shown instead of the hash of the previous file.
     create_effect(move |_| {
-        input_hash.set(run_algorithm(
-            algorithm.get(),
-            file_contents.get(),
-            size.get(),
-        ));
+        if let Some(path) = file_path.get() {
+            match File::open(path)
+                .and_then(|file| run_algorithm_reader(algorithm.get(), file, size.get()))
+            {
+                Ok(hash) => input_hash.set(hash),
+                Err(err) => input_hash.set(format!("Kunne ikke læse filen: {}", err)),
+            }
+        }
     });
 
     v_stack((h_stack((
@@ -116,9 +145,7 @@ fn file_user_input(
                 move |file_info| {
                     if let Some(file) = file_info {
                         selected_file.set(file.path[0].clone().to_string_lossy().to_string());
-                        if let Ok(contents) = fs::read(file.path[0].clone()) {
-                            file_contents.set(contents)
-                        }
+                        file_path.set(Some(file.path[0].clone()));
                     }
                 },
             );